@@ -1,6 +1,10 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracterror, contracttype, Address, Env, Map, symbol_short};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractclient, contractimpl, contracterror, contracttype, token, vec, xdr::ToXdr, Address, Bytes,
+    BytesN, Env, IntoVal, Symbol, Vec, symbol_short,
+};
 
 // Contract errors
 #[contracterror]
@@ -15,9 +19,46 @@ pub enum Error {
     PoolNotFunding = 6,
     DeadlineNotReached = 7,
     GoalNotMet = 8,
-    GoalAlreadyMet = 9,
     NoContribution = 10,
     PoolNotFailed = 11,
+    Overflow = 12,
+    BalanceInvariant = 13,
+    PoolNotFunded = 14,
+    FundsAlreadyClaimed = 15,
+    TierNotFound = 16,
+    TierSoldOut = 17,
+    BelowTierMinimum = 18,
+    TierAlreadySelected = 19,
+    NoTierClaim = 20,
+    ShareTokenNotSet = 21,
+    ShareTokenAlreadySet = 22,
+    SharesAlreadyClaimed = 23,
+    HardCapReached = 24,
+    BelowMinimumContribution = 25,
+    ContributionLimitExceeded = 26,
+    NotVerified = 27,
+    InvalidProof = 28,
+    AttestationExpired = 29,
+    NoExtension = 30,
+    ExtensionPending = 31,
+    VotingClosed = 32,
+    VotingNotEnded = 33,
+    AlreadyVoted = 34,
+    WithdrawalNotAllowed = 35,
+    NoDisbursementPlan = 36,
+    DisbursementPlanActive = 37,
+    InvalidPlan = 38,
+    TrancheVotePending = 39,
+    NoTrancheRequest = 40,
+    NothingToClaim = 41,
+    TokenNotAccepted = 42,
+    TokenAlreadyAccepted = 43,
+    TooManyTokens = 44,
+    OracleNotSet = 45,
+    PriceUnavailable = 46,
+    StalePrice = 47,
+    InvalidReferrer = 48,
+    InvalidShareToken = 49,
 }
 
 // Pool status enum
@@ -27,19 +68,279 @@ pub enum PoolStatus {
     Funding,
     Funded,
     Failed,
+    Abandoned, // Backers voted to abandon; unreleased funds are refundable pro-rata
+    Cancelled, // Called off by the owner while funding; contributions are refundable
+}
+
+// How the pool settles at the deadline
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FundingMode {
+    AllOrNothing,    // Funded only if the goal is met, otherwise everything is refundable
+    KeepItAll(i128), // Owner keeps whatever is raised, provided it reaches this minimum
+}
+
+// Reward tier offered to backers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardTier {
+    pub id: u32,
+    pub min_pledge: i128,
+    pub limit: u32, // 0 for unlimited
+    pub claimed: u32,
+    pub metadata_hash: BytesN<32>,
+}
+
+// Tier selected by a backer
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TierClaim {
+    pub tier_id: u32,
+    pub fulfilled: bool,
+}
+
+// Hard cap on the total raised
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HardCap {
+    pub amount: i128,
+    pub trim_overflow: bool, // Accept the part of a contribution up to the cap instead of rejecting it
+    pub close_on_cap: bool,  // Mark the pool funded as soon as the cap is reached
+}
+
+// Per-contributor contribution limits, 0 meaning no limit
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContributionLimits {
+    pub min_contribution: i128,    // Per contribution
+    pub max_per_contributor: i128, // Cumulative per address
+}
+
+// Cooling-off rules for withdrawing a pledge while funding, 0 meaning no restriction
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalPolicy {
+    pub window: u64,  // Seconds after the backer's last contribution during which they may withdraw
+    pub lockout: u64, // Seconds before the deadline during which withdrawals are blocked
+}
+
+// Who may contribute to the pool
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AccessPolicy {
+    Open,
+    MerkleRoot(BytesN<32>),  // Allowlist root over sha256 of each contributor's address XDR
+    Attestation(BytesN<32>), // ed25519 public key of the attestation issuer
+}
+
+// Proof a contributor presents to get verified
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AccessProof {
+    Merkle(Vec<BytesN<32>>),
+    Attestation(u64, BytesN<64>), // Expiry timestamp and issuer signature
+}
+
+// Verification recorded for a contributor under a given access policy
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccessGrant {
+    pub policy_version: u32,
+    pub expires_at: u64,
+}
+
+// Outcome of a deadline extension vote
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExtensionStatus {
+    Voting,
+    Approved,
+    Rejected,
+}
+
+// Backers allowed to withdraw once an extension is approved
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExtensionWithdrawal {
+    Opponents,  // Only backers who voted against
+    AllBackers,
+}
+
+// Deadline extension proposed by the project owner
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeadlineExtension {
+    pub id: u32,
+    pub new_deadline: u64,
+    pub voting_ends: u64,
+    pub withdrawal: ExtensionWithdrawal,
+    pub votes_for: i128,     // Contribution-weighted
+    pub votes_against: i128, // Contribution-weighted
+    pub status: ExtensionStatus,
+}
+
+// How each tranche of a disbursement plan gets approved
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TrancheApproval {
+    Vote,             // Released if contribution-weighted votes in favour outweigh those against
+    NoObjection(u32), // Released unless objections reach this share of the total raised, in bps
+}
+
+// Raised funds unlocked tranche by tranche instead of claimed at once
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisbursementPlan {
+    pub tranches: Vec<u32>, // Share of the total raised per tranche, in bps summing to 10,000
+    pub approval: TrancheApproval,
+    pub voting_period: u64,
+    pub released: u32,   // Number of tranches paid out
+    pub disbursed: i128, // Amount paid out so far
+}
+
+// Outcome of a tranche release request
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TrancheStatus {
+    Voting,
+    Released,
+    Rejected,
+}
+
+// Owner's request to release the next tranche
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrancheRequest {
+    pub id: u32,
+    pub tranche: u32,
+    pub voting_ends: u64,
+    pub votes_for: i128,     // Contribution-weighted
+    pub votes_against: i128, // Contribution-weighted
+    pub status: TrancheStatus,
+}
+
+// Oracle valuing non-funding-token contributions in the funding goal's unit
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceOracle {
+    pub oracle: Address,
+    pub max_price_age: u64, // Seconds a reported price stays usable
+}
+
+// Asset identifier, as in SEP-40
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+// Price record, as in SEP-40
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
 }
 
 // Storage keys
 #[contracttype]
 pub enum DataKey {
     ProjectOwner,
+    Token,  // SEP-41 token the funding goal is denominated in
+    Tokens, // Funding token followed by any other accepted tokens
+    PriceOracle,
+    TokenRaised(Address),                // Raw amount held per token
+    TokenDisbursed(Address),             // Raw amount paid out in tranches per token
+    TokenContribution(Address, Address), // Raw amount per contributor and token, persistent
     FundingGoal,
+    FundingMode,
     Deadline,
     TotalRaised,
-    Contributors,
+    ContributorCount,
+    Contribution(Address), // Per-contributor total, persistent
+    ContributorAt(u32),    // Contributor index, persistent
     Status,
     CreatedAt,
     Initialized,
+    FundsClaimed,
+    Tiers,
+    TierClaim(Address), // Per-backer TierClaim, persistent
+    ShareToken,
+    SharesClaimed(Address), // Persistent
+    HardCap,
+    StretchGoals,        // Ascending thresholds
+    StretchGoalsReached, // Number of thresholds crossed so far
+    ContributionLimits,
+    WithdrawalPolicy,
+    LastContributedAt(Address), // Persistent
+    AccessPolicy,
+    AccessPolicyVersion,       // Bumped on every policy change, invalidating earlier grants
+    AccessGrant(Address),      // Persistent
+    Extension,                 // Latest DeadlineExtension
+    ExtensionCount,
+    ExtensionVote(u32, Address), // Whether the backer voted in favour, persistent
+    DisbursementPlan,
+    TrancheRequest,            // Latest TrancheRequest
+    TrancheRequestCount,
+    TrancheVote(u32, Address), // Whether the backer voted in favour, persistent
+    AbandonVotes,              // Contribution weight in favour of abandoning
+    AbandonVote(Address),      // Persistent
+    CancelReason,
+    RefundCursor,              // Contributors below this index have been processed by process_refunds
+    UnpaidRefund(Address, Address), // Refund a keeper could not push, per contributor and token, persistent
+    Escrow,                    // Escrow the raised funds were handed off to
+    RevenuePerUnit,            // Cumulative revenue per unit contributed, scaled by REVENUE_PRECISION
+    TotalRevenue,
+    RevenueClaimed(Address),   // Revenue already paid to a backer, persistent
+}
+
+// Basis-point denominator for disbursement tranches and thresholds
+const BPS_DENOMINATOR: i128 = 10_000;
+
+// Storage keys for referral attribution
+#[contracttype]
+pub enum ReferralKey {
+    RewardBps,
+    Referrer(Address),        // Referrer a contributor is attributed to, persistent
+    Volume(Address),          // Value referred per referrer, persistent
+    Amount(Address, Address), // Raw amount referred per referrer and token, persistent
+    Claimed(Address),         // Persistent
+    TotalVolume,              // Value referred across all referrers
+    TokenAmount(Address),     // Raw amount referred per token
+    RewardsPaid,              // Value of rewards claimed across all referrers
+    TokenRewardsPaid(Address), // Raw rewards claimed per token
+}
+
+// Accepted tokens are iterated on every payout, so their number is bounded
+const MAX_TOKENS: u32 = 5;
+
+// Fixed-point scale of the revenue-per-unit accumulator
+const REVENUE_PRECISION: i128 = 1_000_000_000_000;
+
+const CONTRIBUTOR_TTL_THRESHOLD: u32 = 518_400;
+const CONTRIBUTOR_TTL_EXTEND_TO: u32 = 3_110_400;
+
+/// Minting side of the companion share token; the pool must be its admin.
+#[contractclient(name = "ShareTokenClient")]
+pub trait ShareTokenInterface {
+    fn mint(env: Env, to: Address, amount: i128);
+    fn admin(env: Env) -> Address;
+    fn total_supply(env: Env) -> i128;
+}
+
+/// Subset of a SEP-40 price oracle used to value contributions.
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleInterface {
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+}
+
+/// Subset of the escrow contract used to hand raised funds off to a provider.
+#[contractclient(name = "EscrowClient")]
+pub trait EscrowInterface {
+    fn initialize(env: Env, client: Address, provider: Address, release_type: bool);
+    fn add_token(env: Env, token: Address);
+    fn deposit(env: Env, from: Address, token: Address, amount: i128);
 }
 
 #[contract]
@@ -51,8 +352,10 @@ impl PoolContract {
     pub fn initialize(
         env: Env,
         project_owner: Address,
+        token: Address,
         funding_goal: i128,
         deadline: u64,
+        mode: FundingMode,
     ) -> Result<(), Error> {
         // Check if already initialized
         if env.storage().instance().has(&DataKey::Initialized) {
@@ -64,6 +367,12 @@ impl PoolContract {
             return Err(Error::InvalidAmount);
         }
 
+        if let FundingMode::KeepItAll(minimum) = mode {
+            if minimum < 0 || minimum > funding_goal {
+                return Err(Error::InvalidAmount);
+            }
+        }
+
         let current_time = env.ledger().timestamp();
         if deadline <= current_time {
             return Err(Error::InvalidDeadline);
@@ -74,31 +383,39 @@ impl PoolContract {
 
         // Store pool data
         env.storage().instance().set(&DataKey::ProjectOwner, &project_owner);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::Tokens, &vec![&env, token.clone()]);
         env.storage().instance().set(&DataKey::FundingGoal, &funding_goal);
+        env.storage().instance().set(&DataKey::FundingMode, &mode);
         env.storage().instance().set(&DataKey::Deadline, &deadline);
         env.storage().instance().set(&DataKey::TotalRaised, &0i128);
         env.storage().instance().set(&DataKey::Status, &PoolStatus::Funding);
         env.storage().instance().set(&DataKey::CreatedAt, &current_time);
         env.storage().instance().set(&DataKey::Initialized, &true);
 
-        // Initialize empty contributors map
-        let contributors: Map<Address, i128> = Map::new(&env);
-        env.storage().instance().set(&DataKey::Contributors, &contributors);
+        // Contributors are stored individually in persistent storage
+        env.storage().instance().set(&DataKey::ContributorCount, &0u32);
+        env.storage().instance().set(&DataKey::Tiers, &Vec::<RewardTier>::new(&env));
+        env.storage().instance().set(&DataKey::StretchGoals, &Vec::<i128>::new(&env));
+        env.storage().instance().set(&DataKey::StretchGoalsReached, &0u32);
 
         // Emit initialization event
         env.events().publish(
             (symbol_short!("init"),),
-            (project_owner, funding_goal, deadline)
+            (project_owner, token, funding_goal, deadline, mode)
         );
 
         Ok(())
     }
 
-    /// Contribute to the pool
-    pub fn contribute(env: Env, contributor: Address, amount: i128) -> Result<(), Error> {
-        contributor.require_auth();
+    /// Add a reward tier (project owner only, while funding)
+    pub fn add_tier(env: Env, min_pledge: i128, limit: u32, metadata_hash: BytesN<32>) -> Result<u32, Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
 
-        if amount <= 0 {
+        project_owner.require_auth();
+
+        if min_pledge <= 0 {
             return Err(Error::InvalidAmount);
         }
 
@@ -109,41 +426,68 @@ impl PoolContract {
             return Err(Error::PoolNotFunding);
         }
 
-        // Check deadline hasn't passed
-        let deadline: u64 = env.storage().instance().get(&DataKey::Deadline)
+        let mut tiers: Vec<RewardTier> = env.storage().instance().get(&DataKey::Tiers)
             .ok_or(Error::NotInitialized)?;
-        let current_time = env.ledger().timestamp();
-        
-        if current_time >= deadline {
-            return Err(Error::DeadlineNotReached);
-        }
 
-        // Update contributor's total contribution
-        let mut contributors: Map<Address, i128> = env.storage().instance()
-            .get(&DataKey::Contributors)
+        let id = tiers.len();
+        tiers.push_back(RewardTier {
+            id,
+            min_pledge,
+            limit,
+            claimed: 0,
+            metadata_hash,
+        });
+        env.storage().instance().set(&DataKey::Tiers, &tiers);
+
+        // Emit tier event
+        env.events().publish(
+            (symbol_short!("tier"),),
+            (id, min_pledge, limit)
+        );
+
+        Ok(id)
+    }
+
+    /// Set a hard cap on the total raised (project owner only, while funding)
+    pub fn set_hard_cap(env: Env, amount: i128, trim_overflow: bool, close_on_cap: bool) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
             .ok_or(Error::NotInitialized)?;
 
-        let current_contribution = contributors.get(contributor.clone()).unwrap_or(0);
-        contributors.set(contributor.clone(), current_contribution + amount);
+        project_owner.require_auth();
 
-        // Update total raised
-        let mut total_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised).unwrap();
-        total_raised += amount;
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
 
-        env.storage().instance().set(&DataKey::TotalRaised, &total_raised);
-        env.storage().instance().set(&DataKey::Contributors, &contributors);
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
 
-        // Emit contribution event
-        env.events().publish(
-            (symbol_short!("contrib"),),
-            (contributor, amount, total_raised)
-        );
+        let funding_goal: i128 = env.storage().instance().get(&DataKey::FundingGoal)
+            .ok_or(Error::NotInitialized)?;
+        let total_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised)
+            .ok_or(Error::NotInitialized)?;
+        let stretch_goals: Vec<i128> = env.storage().instance().get(&DataKey::StretchGoals)
+            .ok_or(Error::NotInitialized)?;
+
+        // The cap must cover the goal and every stretch goal, and sit above the funds
+        // already raised so the pool can still reach it through a contribution
+        let highest_goal = stretch_goals.last().unwrap_or(funding_goal);
+        if amount < highest_goal || amount <= total_raised {
+            return Err(Error::InvalidAmount);
+        }
+
+        env.storage().instance().set(&DataKey::HardCap, &HardCap { amount, trim_overflow, close_on_cap });
 
         Ok(())
     }
 
-    /// Finalize the pool (check if goal met and update status)
-    pub fn finalize(env: Env) -> Result<(), Error> {
+    /// Add a stretch goal above the funding goal (project owner only, while funding)
+    pub fn add_stretch_goal(env: Env, threshold: i128) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
+
         let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
             .ok_or(Error::NotInitialized)?;
 
@@ -151,108 +495,2066 @@ impl PoolContract {
             return Err(Error::PoolNotFunding);
         }
 
-        // Check deadline has passed
-        let deadline: u64 = env.storage().instance().get(&DataKey::Deadline)
+        let funding_goal: i128 = env.storage().instance().get(&DataKey::FundingGoal)
             .ok_or(Error::NotInitialized)?;
-        let current_time = env.ledger().timestamp();
-        
-        if current_time < deadline {
-            return Err(Error::DeadlineNotReached);
+        let mut stretch_goals: Vec<i128> = env.storage().instance().get(&DataKey::StretchGoals)
+            .ok_or(Error::NotInitialized)?;
+
+        // Thresholds must be added in ascending order, above the goal and within the cap
+        let highest_goal = stretch_goals.last().unwrap_or(funding_goal);
+        if threshold <= highest_goal {
+            return Err(Error::InvalidAmount);
         }
 
-        let total_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised)
+        let hard_cap: Option<HardCap> = env.storage().instance().get(&DataKey::HardCap);
+        if let Some(cap) = hard_cap {
+            if threshold > cap.amount {
+                return Err(Error::InvalidAmount);
+            }
+        }
+
+        stretch_goals.push_back(threshold);
+        env.storage().instance().set(&DataKey::StretchGoals, &stretch_goals);
+
+        Ok(())
+    }
+
+    /// Set per-contributor limits (project owner only, while funding)
+    pub fn set_contribution_limits(env: Env, min_contribution: i128, max_per_contributor: i128) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
             .ok_or(Error::NotInitialized)?;
-        let funding_goal: i128 = env.storage().instance().get(&DataKey::FundingGoal)
+
+        project_owner.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
             .ok_or(Error::NotInitialized)?;
 
-        let new_status = if total_raised >= funding_goal {
-            PoolStatus::Funded
-        } else {
-            PoolStatus::Failed
-        };
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
 
-        env.storage().instance().set(&DataKey::Status, &new_status);
+        if min_contribution < 0 || max_per_contributor < 0 {
+            return Err(Error::InvalidAmount);
+        }
 
-        // Emit finalization event
-        env.events().publish(
-            (symbol_short!("finalize"),),
-            (new_status.clone(), total_raised)
+        if max_per_contributor > 0 && min_contribution > max_per_contributor {
+            return Err(Error::InvalidAmount);
+        }
+
+        env.storage().instance().set(
+            &DataKey::ContributionLimits,
+            &ContributionLimits { min_contribution, max_per_contributor },
         );
 
         Ok(())
     }
 
-    /// Request refund (only if pool failed)
-    pub fn refund(env: Env, contributor: Address) -> Result<i128, Error> {
-        contributor.require_auth();
+    /// Set cooling-off rules for `withdraw_contribution` (project owner only, while funding)
+    pub fn set_withdrawal_policy(env: Env, window: u64, lockout: u64) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
 
         let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
             .ok_or(Error::NotInitialized)?;
 
-        if status != PoolStatus::Failed {
-            return Err(Error::PoolNotFailed);
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
         }
 
-        let mut contributors: Map<Address, i128> = env.storage().instance()
-            .get(&DataKey::Contributors)
+        env.storage().instance().set(&DataKey::WithdrawalPolicy, &WithdrawalPolicy { window, lockout });
+
+        Ok(())
+    }
+
+    /// Set the price oracle used to value contributions in other tokens
+    /// (project owner only, while funding)
+    pub fn set_price_oracle(env: Env, oracle: Address, max_price_age: u64) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
             .ok_or(Error::NotInitialized)?;
 
-        let contribution = contributors.get(contributor.clone())
-            .ok_or(Error::NoContribution)?;
+        project_owner.require_auth();
 
-        if contribution == 0 {
-            return Err(Error::NoContribution);
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
+
+        env.storage().instance().set(&DataKey::PriceOracle, &PriceOracle { oracle, max_price_age });
+
+        Ok(())
+    }
+
+    /// Accept contributions in another token, valued through the price oracle
+    /// (project owner only, while funding)
+    pub fn add_accepted_token(env: Env, token: Address) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
+
+        if !env.storage().instance().has(&DataKey::PriceOracle) {
+            return Err(Error::OracleNotSet);
+        }
+
+        let mut tokens = accepted_tokens(&env)?;
+        if tokens.contains(&token) {
+            return Err(Error::TokenAlreadyAccepted);
+        }
+
+        if tokens.len() >= MAX_TOKENS {
+            return Err(Error::TooManyTokens);
         }
 
-        // Remove contributor's entry (set to 0)
-        contributors.set(contributor.clone(), 0);
-        env.storage().instance().set(&DataKey::Contributors, &contributors);
+        tokens.push_back(token.clone());
+        env.storage().instance().set(&DataKey::Tokens, &tokens);
 
-        // Emit refund event
+        // Emit accepted token event
         env.events().publish(
-            (symbol_short!("refund"),),
-            (contributor, contribution)
+            (symbol_short!("token"),),
+            token
         );
 
-        Ok(contribution)
+        Ok(())
     }
 
-    /// Get pool status
-    pub fn get_status(env: Env) -> Result<PoolStatus, Error> {
-        env.storage().instance().get(&DataKey::Status)
-            .ok_or(Error::NotInitialized)
-    }
+    /// Set the reward paid to referrers on success, as a share of the volume
+    /// they referred in bps (project owner only, while funding)
+    pub fn set_referral_reward(env: Env, reward_bps: u32) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
 
-    /// Get total raised amount
-    pub fn get_total_raised(env: Env) -> Result<i128, Error> {
-        env.storage().instance().get(&DataKey::TotalRaised)
-            .ok_or(Error::NotInitialized)
-    }
+        project_owner.require_auth();
 
-    /// Get funding goal
-    pub fn get_funding_goal(env: Env) -> Result<i128, Error> {
-        env.storage().instance().get(&DataKey::FundingGoal)
-            .ok_or(Error::NotInitialized)
-    }
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
 
-    /// Get deadline
-    pub fn get_deadline(env: Env) -> Result<u64, Error> {
-        env.storage().instance().get(&DataKey::Deadline)
-            .ok_or(Error::NotInitialized)
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
+
+        if reward_bps as i128 > BPS_DENOMINATOR {
+            return Err(Error::InvalidAmount);
+        }
+
+        env.storage().instance().set(&ReferralKey::RewardBps, &reward_bps);
+
+        Ok(())
     }
 
-    /// Get contributor's contribution amount
-    pub fn get_contribution(env: Env, contributor: Address) -> Result<i128, Error> {
-        let contributors: Map<Address, i128> = env.storage().instance()
-            .get(&DataKey::Contributors)
+    /// Restrict who may contribute (project owner only, while funding).
+    /// Changing the policy invalidates all earlier verifications.
+    pub fn set_access_policy(env: Env, policy: AccessPolicy) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
             .ok_or(Error::NotInitialized)?;
 
-        Ok(contributors.get(contributor).unwrap_or(0))
-    }
+        project_owner.require_auth();
 
-    /// Get project owner
-    pub fn get_project_owner(env: Env) -> Result<Address, Error> {
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
+
+        let version: u32 = env.storage().instance().get(&DataKey::AccessPolicyVersion).unwrap_or(0);
+        env.storage().instance().set(&DataKey::AccessPolicyVersion, &version.checked_add(1).ok_or(Error::Overflow)?);
+        env.storage().instance().set(&DataKey::AccessPolicy, &policy);
+
+        Ok(())
+    }
+
+    /// Verify a contributor against the access policy with an allowlist proof
+    /// or issuer attestation. Attestations are signed over the XDR of
+    /// `(pool address, contributor, expires_at)`; an invalid signature traps.
+    pub fn verify_participant(env: Env, contributor: Address, proof: AccessProof) -> Result<(), Error> {
+        let policy: AccessPolicy = env.storage().instance().get(&DataKey::AccessPolicy)
+            .unwrap_or(AccessPolicy::Open);
+
+        let expires_at = match (policy, proof) {
+            (AccessPolicy::MerkleRoot(root), AccessProof::Merkle(path)) => {
+                let mut node: BytesN<32> = env.crypto().sha256(&contributor.clone().to_xdr(&env)).to_bytes();
+                for sibling in path.iter() {
+                    // Pairs are hashed in sorted order so proofs don't need direction bits
+                    let mut pair = Bytes::new(&env);
+                    if node <= sibling {
+                        pair.extend_from_array(&node.to_array());
+                        pair.extend_from_array(&sibling.to_array());
+                    } else {
+                        pair.extend_from_array(&sibling.to_array());
+                        pair.extend_from_array(&node.to_array());
+                    }
+                    node = env.crypto().sha256(&pair).to_bytes();
+                }
+                if node != root {
+                    return Err(Error::InvalidProof);
+                }
+                u64::MAX
+            }
+            (AccessPolicy::Attestation(issuer), AccessProof::Attestation(expires_at, signature)) => {
+                if expires_at <= env.ledger().timestamp() {
+                    return Err(Error::AttestationExpired);
+                }
+                let message = (env.current_contract_address(), contributor.clone(), expires_at).to_xdr(&env);
+                env.crypto().ed25519_verify(&issuer, &message, &signature);
+                expires_at
+            }
+            _ => return Err(Error::InvalidProof),
+        };
+
+        let policy_version: u32 = env.storage().instance().get(&DataKey::AccessPolicyVersion).unwrap_or(0);
+        let key = DataKey::AccessGrant(contributor.clone());
+        env.storage().persistent().set(&key, &AccessGrant { policy_version, expires_at });
+        env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+        // Emit verification event
+        env.events().publish(
+            (symbol_short!("verified"),),
+            (contributor, expires_at)
+        );
+
+        Ok(())
+    }
+
+    /// Contribute `amount` of an accepted token, optionally selecting a reward tier.
+    /// Contributions are recorded at their value in the funding token; goals, caps,
+    /// limits and tiers all apply to that value. Returns the amount of `token`
+    /// accepted, which is less than `amount` when trimmed at the hard cap.
+    ///
+    /// A `referrer` given with a contributor's first contribution is credited with
+    /// all of that contributor's contributions; later referrers are ignored.
+    pub fn contribute(
+        env: Env,
+        contributor: Address,
+        token: Address,
+        amount: i128,
+        tier: Option<u32>,
+        referrer: Option<Address>,
+    ) -> Result<i128, Error> {
+        contributor.require_auth();
+
+        if referrer.as_ref() == Some(&contributor) {
+            return Err(Error::InvalidReferrer);
+        }
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
+
+        // Check deadline hasn't passed
+        let deadline: u64 = env.storage().instance().get(&DataKey::Deadline)
+            .ok_or(Error::NotInitialized)?;
+        let current_time = env.ledger().timestamp();
+        
+        if current_time >= deadline {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        check_access(&env, &contributor)?;
+
+        let mut value = value_of(&env, &token, amount)?;
+        if value <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let limits: Option<ContributionLimits> = env.storage().instance().get(&DataKey::ContributionLimits);
+        if let Some(limits) = &limits {
+            if value < limits.min_contribution {
+                return Err(Error::BelowMinimumContribution);
+            }
+        }
+
+        let previously_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised)
+            .ok_or(Error::NotInitialized)?;
+
+        // Enforce the hard cap, trimming the contribution if configured
+        let hard_cap: Option<HardCap> = env.storage().instance().get(&DataKey::HardCap);
+        let mut amount = amount;
+        if let Some(cap) = &hard_cap {
+            let room = cap.amount.checked_sub(previously_raised).ok_or(Error::Overflow)?;
+            if room <= 0 || (value > room && !cap.trim_overflow) {
+                return Err(Error::HardCapReached);
+            }
+            if value > room {
+                // Scale the contribution down to the room left; rounding favours the cap
+                amount = amount.checked_mul(room).ok_or(Error::Overflow)? / value;
+                value = value_of(&env, &token, amount)?.min(room);
+                if amount <= 0 || value <= 0 {
+                    return Err(Error::HardCapReached);
+                }
+            }
+        }
+
+        // Update contributor's total contribution
+        let current_contribution = contribution_of(&env, &contributor);
+        let new_contribution = current_contribution.checked_add(value).ok_or(Error::Overflow)?;
+
+        if let Some(limits) = &limits {
+            if limits.max_per_contributor > 0 && new_contribution > limits.max_per_contributor {
+                return Err(Error::ContributionLimitExceeded);
+            }
+        }
+
+        // Update total raised
+        let total_raised = previously_raised.checked_add(value).ok_or(Error::Overflow)?;
+        let token_raised = token_raised(&env, &token).checked_add(amount).ok_or(Error::Overflow)?;
+        let token_contribution = token_contribution_of(&env, &contributor, &token)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+
+        // A single contribution can never exceed everything raised
+        if new_contribution > total_raised {
+            return Err(Error::BalanceInvariant);
+        }
+
+        // Take custody of the contribution
+        token::Client::new(&env, &token).transfer(&contributor, &env.current_contract_address(), &amount);
+
+        // Index first-time contributors
+        if !env.storage().persistent().has(&DataKey::Contribution(contributor.clone())) {
+            let count: u32 = env.storage().instance().get(&DataKey::ContributorCount)
+                .ok_or(Error::NotInitialized)?;
+            let key = DataKey::ContributorAt(count);
+            env.storage().persistent().set(&key, &contributor);
+            env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+            env.storage().instance().set(&DataKey::ContributorCount, &count.checked_add(1).ok_or(Error::Overflow)?);
+        }
+
+        env.storage().instance().set(&DataKey::TotalRaised, &total_raised);
+        set_contribution(&env, &contributor, new_contribution);
+        env.storage().instance().set(&DataKey::TokenRaised(token.clone()), &token_raised);
+        set_token_contribution(&env, &contributor, &token, token_contribution);
+
+        // Attribute the contribution to the contributor's referrer, binding one on their first contribution
+        let referrer_key = ReferralKey::Referrer(contributor.clone());
+        let mut bound: Option<Address> = env.storage().persistent().get(&referrer_key);
+        if bound.is_none() && current_contribution == 0 {
+            if let Some(referrer) = referrer {
+                env.storage().persistent().set(&referrer_key, &referrer);
+                env.storage().persistent().extend_ttl(&referrer_key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+                bound = Some(referrer);
+            }
+        }
+        if let Some(referrer) = bound {
+            add_referred_amount(&env, &referrer, &token, amount)?;
+            add_referred_volume(&env, &referrer, value)?;
+
+            // Emit referral event
+            env.events().publish(
+                (symbol_short!("referral"),),
+                (referrer, contributor.clone(), token.clone(), amount, value)
+            );
+        }
+
+        let key = DataKey::LastContributedAt(contributor.clone());
+        env.storage().persistent().set(&key, &current_time);
+        env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+        if let Some(tier_id) = tier {
+            select_tier(&env, &contributor, tier_id, new_contribution)?;
+        }
+
+        // Emit contribution event
+        env.events().publish(
+            (symbol_short!("contrib"),),
+            (contributor, token, amount, value, total_raised)
+        );
+
+        // Emit an event for each stretch goal crossed by this contribution
+        let stretch_goals: Vec<i128> = env.storage().instance().get(&DataKey::StretchGoals)
+            .ok_or(Error::NotInitialized)?;
+        let mut reached: u32 = env.storage().instance().get(&DataKey::StretchGoalsReached)
+            .ok_or(Error::NotInitialized)?;
+        while let Some(threshold) = stretch_goals.get(reached) {
+            if total_raised < threshold {
+                break;
+            }
+            env.events().publish(
+                (symbol_short!("stretch"),),
+                (reached, threshold, total_raised)
+            );
+            reached += 1;
+        }
+        env.storage().instance().set(&DataKey::StretchGoalsReached, &reached);
+
+        // Close funding early once the cap is reached, if configured
+        if let Some(cap) = hard_cap {
+            if cap.close_on_cap && total_raised >= cap.amount {
+                env.storage().instance().set(&DataKey::Status, &PoolStatus::Funded);
+
+                // Emit finalization event
+                env.events().publish(
+                    (symbol_short!("finalize"),),
+                    (PoolStatus::Funded, total_raised)
+                );
+            }
+        }
+
+        Ok(amount)
+    }
+
+    /// Withdraw a pledge before the deadline, subject to the withdrawal policy.
+    /// Backers who voted on an open deadline extension must wait for it to resolve.
+    pub fn withdraw_contribution(env: Env, contributor: Address) -> Result<i128, Error> {
+        contributor.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
+
+        let deadline: u64 = env.storage().instance().get(&DataKey::Deadline)
+            .ok_or(Error::NotInitialized)?;
+        let current_time = env.ledger().timestamp();
+
+        if current_time >= deadline {
+            return Err(Error::WithdrawalNotAllowed);
+        }
+
+        let policy: Option<WithdrawalPolicy> = env.storage().instance().get(&DataKey::WithdrawalPolicy);
+        if let Some(policy) = policy {
+            if policy.lockout > 0 && current_time >= deadline.saturating_sub(policy.lockout) {
+                return Err(Error::WithdrawalNotAllowed);
+            }
+
+            if policy.window > 0 {
+                let last_contributed: u64 = env.storage().persistent()
+                    .get(&DataKey::LastContributedAt(contributor.clone()))
+                    .ok_or(Error::NoContribution)?;
+                if current_time >= last_contributed.saturating_add(policy.window) {
+                    return Err(Error::WithdrawalNotAllowed);
+                }
+            }
+        }
+
+        // Votes are weighted at the time they are cast, so they can't be withdrawn from under the tally
+        let extension: Option<DeadlineExtension> = env.storage().instance().get(&DataKey::Extension);
+        if let Some(extension) = extension {
+            if extension.status == ExtensionStatus::Voting
+                && env.storage().persistent().has(&DataKey::ExtensionVote(extension.id, contributor.clone()))
+            {
+                return Err(Error::ExtensionPending);
+            }
+        }
+
+        withdraw_pledge(&env, &contributor)
+    }
+
+    /// Call off the campaign (project owner only, while funding). All contributions
+    /// become refundable immediately.
+    pub fn cancel(env: Env, reason_hash: BytesN<32>) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
+
+        env.storage().instance().set(&DataKey::Status, &PoolStatus::Cancelled);
+        env.storage().instance().set(&DataKey::CancelReason, &reason_hash);
+
+        let total_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised)
+            .ok_or(Error::NotInitialized)?;
+
+        // Emit cancellation event
+        env.events().publish(
+            (symbol_short!("cancel"),),
+            (project_owner, reason_hash, total_raised)
+        );
+
+        Ok(())
+    }
+
+    /// Finalize the pool (check if goal met and update status)
+    pub fn finalize(env: Env) -> Result<(), Error> {
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
+
+        // Check deadline has passed
+        let deadline: u64 = env.storage().instance().get(&DataKey::Deadline)
+            .ok_or(Error::NotInitialized)?;
+        let current_time = env.ledger().timestamp();
+        
+        if current_time < deadline {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        // An open extension vote has to be resolved first
+        let extension: Option<DeadlineExtension> = env.storage().instance().get(&DataKey::Extension);
+        if let Some(extension) = extension {
+            if extension.status == ExtensionStatus::Voting {
+                return Err(Error::ExtensionPending);
+            }
+        }
+
+        let total_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised)
+            .ok_or(Error::NotInitialized)?;
+        let funding_goal: i128 = env.storage().instance().get(&DataKey::FundingGoal)
+            .ok_or(Error::NotInitialized)?;
+        let mode: FundingMode = env.storage().instance().get(&DataKey::FundingMode)
+            .ok_or(Error::NotInitialized)?;
+
+        let funded = match mode {
+            FundingMode::AllOrNothing => total_raised >= funding_goal,
+            FundingMode::KeepItAll(minimum) => total_raised > 0 && total_raised >= minimum,
+        };
+
+        let new_status = if funded {
+            PoolStatus::Funded
+        } else {
+            PoolStatus::Failed
+        };
+
+        env.storage().instance().set(&DataKey::Status, &new_status);
+
+        // Emit finalization event
+        env.events().publish(
+            (symbol_short!("finalize"),),
+            (new_status.clone(), total_raised)
+        );
+
+        Ok(())
+    }
+
+    /// Request refund (only if pool failed: below the goal in all-or-nothing
+    /// mode, or below the minimum in keep-it-all mode; or was cancelled). Abandoned pools refund
+    /// each backer's pro-rata share of the funds not yet disbursed.
+    pub fn refund(env: Env, contributor: Address) -> Result<i128, Error> {
+        contributor.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if !matches!(status, PoolStatus::Failed | PoolStatus::Abandoned | PoolStatus::Cancelled) {
+            return Err(Error::PoolNotFailed);
+        }
+
+        let contribution = contribution_of(&env, &contributor);
+
+        if contribution == 0 {
+            return Err(Error::NoContribution);
+        }
+
+        refund_contributor(&env, &status, &contributor, contribution, false)
+    }
+
+    /// Push refunds to contributors `start..start + limit` of a failed, abandoned or
+    /// cancelled pool (anyone). Pages never start below the refund cursor, contributors
+    /// already refunded are skipped, and a transfer that fails (e.g. a missing trustline)
+    /// is recorded as an unpaid refund for the contributor to claim instead of reverting
+    /// the page. Returns the number of contributors processed.
+    pub fn process_refunds(env: Env, start: u32, limit: u32) -> Result<u32, Error> {
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if !matches!(status, PoolStatus::Failed | PoolStatus::Abandoned | PoolStatus::Cancelled) {
+            return Err(Error::PoolNotFailed);
+        }
+
+        let count: u32 = env.storage().instance().get(&DataKey::ContributorCount)
+            .ok_or(Error::NotInitialized)?;
+        let mut cursor: u32 = env.storage().instance().get(&DataKey::RefundCursor).unwrap_or(0);
+        let start = start.max(cursor);
+        let end = start.saturating_add(limit).min(count);
+
+        let mut refunded: u32 = 0;
+        for index in start..end {
+            let contributor: Address = env.storage().persistent().get(&DataKey::ContributorAt(index))
+                .ok_or(Error::NotInitialized)?;
+            let contribution = contribution_of(&env, &contributor);
+            if contribution == 0 {
+                continue;
+            }
+
+            refund_contributor(&env, &status, &contributor, contribution, true)?;
+            refunded += 1;
+        }
+
+        // Move the cursor past this page if it joins up with it, then over any
+        // contributors already processed by earlier out-of-order pages
+        if start == cursor {
+            cursor = end;
+            let scan_end = cursor.saturating_add(limit).min(count);
+            while cursor < scan_end {
+                let contributor: Address = env.storage().persistent().get(&DataKey::ContributorAt(cursor))
+                    .ok_or(Error::NotInitialized)?;
+                if contribution_of(&env, &contributor) != 0 {
+                    break;
+                }
+                cursor += 1;
+            }
+            env.storage().instance().set(&DataKey::RefundCursor, &cursor);
+        }
+
+        // Emit batch refund event
+        env.events().publish(
+            (symbol_short!("refunds"),),
+            (start, end, refunded)
+        );
+
+        Ok(refunded)
+    }
+
+    /// Contributor claims a refund that `process_refunds` could not push to them
+    pub fn claim_unpaid_refund(env: Env, contributor: Address, token: Address) -> Result<i128, Error> {
+        contributor.require_auth();
+
+        let key = DataKey::UnpaidRefund(contributor.clone(), token.clone());
+        let amount: i128 = env.storage().persistent().get(&key)
+            .ok_or(Error::NothingToClaim)?;
+        env.storage().persistent().remove(&key);
+
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &contributor, &amount);
+
+        // Emit unpaid refund claim event
+        env.events().publish(
+            (symbol_short!("ref_retry"),),
+            (contributor, token, amount)
+        );
+
+        Ok(amount)
+    }
+
+    /// Project owner claims the raised funds (only if pool funded)
+    pub fn claim_funds(env: Env) -> Result<i128, Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funded {
+            return Err(Error::PoolNotFunded);
+        }
+
+        if env.storage().instance().has(&DataKey::FundsClaimed) {
+            return Err(Error::FundsAlreadyClaimed);
+        }
+
+        if env.storage().instance().has(&DataKey::DisbursementPlan) {
+            return Err(Error::DisbursementPlanActive);
+        }
+
+        // Referral rewards stay in the contract for referrers to claim
+        let total_raised = payable_value(&env)?;
+
+        env.storage().instance().set(&DataKey::FundsClaimed, &true);
+
+        for token in accepted_tokens(&env)?.iter() {
+            let amount = payable_raised(&env, &token)?;
+            if amount > 0 {
+                token::Client::new(&env, &token).transfer(&env.current_contract_address(), &project_owner, &amount);
+            }
+        }
+
+        // Emit claim event
+        env.events().publish(
+            (symbol_short!("claim"),),
+            (project_owner, total_raised)
+        );
+
+        Ok(total_raised)
+    }
+
+    /// Attach a disbursement plan (project owner only, while funding). Once funded,
+    /// raised funds are released tranche by tranche instead of through `claim_funds`.
+    pub fn set_disbursement_plan(
+        env: Env,
+        tranches: Vec<u32>,
+        approval: TrancheApproval,
+        voting_period: u64,
+    ) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
+
+        let mut total_bps: i128 = 0;
+        for bps in tranches.iter() {
+            if bps == 0 {
+                return Err(Error::InvalidPlan);
+            }
+            total_bps += bps as i128;
+        }
+
+        if total_bps != BPS_DENOMINATOR || voting_period == 0 {
+            return Err(Error::InvalidPlan);
+        }
+
+        if let TrancheApproval::NoObjection(threshold) = approval {
+            if threshold == 0 || threshold as i128 > BPS_DENOMINATOR {
+                return Err(Error::InvalidPlan);
+            }
+        }
+
+        env.storage().instance().set(&DataKey::DisbursementPlan, &DisbursementPlan {
+            tranches: tranches.clone(),
+            approval,
+            voting_period,
+            released: 0,
+            disbursed: 0,
+        });
+
+        // Emit plan event
+        env.events().publish(
+            (symbol_short!("plan"),),
+            (tranches, voting_period)
+        );
+
+        Ok(())
+    }
+
+    /// Open a backer vote on releasing the next tranche (project owner only, once funded)
+    pub fn request_tranche(env: Env) -> Result<u32, Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funded {
+            return Err(Error::PoolNotFunded);
+        }
+
+        let plan: DisbursementPlan = env.storage().instance().get(&DataKey::DisbursementPlan)
+            .ok_or(Error::NoDisbursementPlan)?;
+
+        if plan.released >= plan.tranches.len() {
+            return Err(Error::FundsAlreadyClaimed);
+        }
+
+        let previous: Option<TrancheRequest> = env.storage().instance().get(&DataKey::TrancheRequest);
+        if let Some(previous) = previous {
+            if previous.status == TrancheStatus::Voting {
+                return Err(Error::TrancheVotePending);
+            }
+        }
+
+        let voting_ends = env.ledger().timestamp().checked_add(plan.voting_period).ok_or(Error::Overflow)?;
+        let id: u32 = env.storage().instance().get(&DataKey::TrancheRequestCount).unwrap_or(0);
+        env.storage().instance().set(&DataKey::TrancheRequestCount, &id.checked_add(1).ok_or(Error::Overflow)?);
+        env.storage().instance().set(&DataKey::TrancheRequest, &TrancheRequest {
+            id,
+            tranche: plan.released,
+            voting_ends,
+            votes_for: 0,
+            votes_against: 0,
+            status: TrancheStatus::Voting,
+        });
+
+        // Emit tranche request event
+        env.events().publish(
+            (symbol_short!("tr_req"),),
+            (id, plan.released, voting_ends)
+        );
+
+        Ok(id)
+    }
+
+    /// Vote on the open tranche request, weighted by the backer's contribution
+    pub fn vote_tranche(env: Env, backer: Address, approve: bool) -> Result<(), Error> {
+        backer.require_auth();
+
+        let mut request: TrancheRequest = env.storage().instance().get(&DataKey::TrancheRequest)
+            .ok_or(Error::NoTrancheRequest)?;
+
+        if request.status != TrancheStatus::Voting || env.ledger().timestamp() >= request.voting_ends {
+            return Err(Error::VotingClosed);
+        }
+
+        let weight = contribution_of(&env, &backer);
+        if weight <= 0 {
+            return Err(Error::NoContribution);
+        }
+
+        let key = DataKey::TrancheVote(request.id, backer.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(Error::AlreadyVoted);
+        }
+
+        if approve {
+            request.votes_for = request.votes_for.checked_add(weight).ok_or(Error::Overflow)?;
+        } else {
+            request.votes_against = request.votes_against.checked_add(weight).ok_or(Error::Overflow)?;
+        }
+
+        env.storage().instance().set(&DataKey::TrancheRequest, &request);
+        env.storage().persistent().set(&key, &approve);
+        env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+        // Emit vote event
+        env.events().publish(
+            (symbol_short!("tr_vote"),),
+            (request.id, backer, approve, weight)
+        );
+
+        Ok(())
+    }
+
+    /// Close the tranche vote once the voting period is over, paying the tranche
+    /// to the project owner if approved. The final tranche pays out any remainder.
+    pub fn release_tranche(env: Env) -> Result<i128, Error> {
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funded {
+            return Err(Error::PoolNotFunded);
+        }
+
+        let mut request: TrancheRequest = env.storage().instance().get(&DataKey::TrancheRequest)
+            .ok_or(Error::NoTrancheRequest)?;
+
+        if request.status != TrancheStatus::Voting {
+            return Err(Error::VotingClosed);
+        }
+
+        if env.ledger().timestamp() < request.voting_ends {
+            return Err(Error::VotingNotEnded);
+        }
+
+        let mut plan: DisbursementPlan = env.storage().instance().get(&DataKey::DisbursementPlan)
+            .ok_or(Error::NoDisbursementPlan)?;
+        let total_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised)
+            .ok_or(Error::NotInitialized)?;
+
+        let approved = match plan.approval {
+            TrancheApproval::Vote => request.votes_for > request.votes_against,
+            TrancheApproval::NoObjection(threshold) => {
+                let objection = request.votes_against.checked_mul(BPS_DENOMINATOR).ok_or(Error::Overflow)?;
+                objection < total_raised.checked_mul(threshold as i128).ok_or(Error::Overflow)?
+            }
+        };
+
+        let mut amount = 0;
+        if approved {
+            let last = request.tranche + 1 == plan.tranches.len();
+            let bps = plan.tranches.get(request.tranche).ok_or(Error::InvalidPlan)? as i128;
+            let payable = payable_value(&env)?;
+            amount = if last {
+                payable - plan.disbursed
+            } else {
+                payable.checked_mul(bps).ok_or(Error::Overflow)? / BPS_DENOMINATOR
+            };
+
+            // Each accepted token is paid out in the same proportion
+            let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+                .ok_or(Error::NotInitialized)?;
+            for token in accepted_tokens(&env)?.iter() {
+                let payable = payable_raised(&env, &token)?;
+                let disbursed: i128 = env.storage().instance().get(&DataKey::TokenDisbursed(token.clone())).unwrap_or(0);
+                let payout = if last {
+                    payable - disbursed
+                } else {
+                    payable.checked_mul(bps).ok_or(Error::Overflow)? / BPS_DENOMINATOR
+                };
+                if payout <= 0 {
+                    continue;
+                }
+
+                let disbursed = disbursed.checked_add(payout).ok_or(Error::Overflow)?;
+                if disbursed > payable {
+                    return Err(Error::BalanceInvariant);
+                }
+                env.storage().instance().set(&DataKey::TokenDisbursed(token.clone()), &disbursed);
+                token::Client::new(&env, &token).transfer(&env.current_contract_address(), &project_owner, &payout);
+            }
+
+            plan.released += 1;
+            plan.disbursed = plan.disbursed.checked_add(amount).ok_or(Error::Overflow)?;
+            if plan.disbursed > payable {
+                return Err(Error::BalanceInvariant);
+            }
+            env.storage().instance().set(&DataKey::DisbursementPlan, &plan);
+            request.status = TrancheStatus::Released;
+        } else {
+            request.status = TrancheStatus::Rejected;
+        }
+        env.storage().instance().set(&DataKey::TrancheRequest, &request);
+
+        // Emit tranche outcome event
+        env.events().publish(
+            (symbol_short!("tr_done"),),
+            (request.id, request.tranche, request.status, amount)
+        );
+
+        Ok(amount)
+    }
+
+    /// Vote to abandon a funded project with a disbursement plan. Once more than
+    /// half of the total raised has voted, the pool is abandoned and the funds
+    /// not yet disbursed become refundable pro-rata.
+    pub fn vote_abandon(env: Env, backer: Address) -> Result<bool, Error> {
+        backer.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funded {
+            return Err(Error::PoolNotFunded);
+        }
+
+        let plan: DisbursementPlan = env.storage().instance().get(&DataKey::DisbursementPlan)
+            .ok_or(Error::NoDisbursementPlan)?;
+
+        if plan.released >= plan.tranches.len() {
+            return Err(Error::FundsAlreadyClaimed);
+        }
+
+        let weight = contribution_of(&env, &backer);
+        if weight <= 0 {
+            return Err(Error::NoContribution);
+        }
+
+        let key = DataKey::AbandonVote(backer.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(Error::AlreadyVoted);
+        }
+
+        let votes: i128 = env.storage().instance().get(&DataKey::AbandonVotes).unwrap_or(0);
+        let votes = votes.checked_add(weight).ok_or(Error::Overflow)?;
+        env.storage().instance().set(&DataKey::AbandonVotes, &votes);
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+        // Emit abandon vote event
+        env.events().publish(
+            (symbol_short!("abandon"),),
+            (backer, weight, votes)
+        );
+
+        let total_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised)
+            .ok_or(Error::NotInitialized)?;
+        let abandoned = votes.checked_mul(2).ok_or(Error::Overflow)? > total_raised;
+
+        if abandoned {
+            env.storage().instance().set(&DataKey::Status, &PoolStatus::Abandoned);
+
+            // Emit abandonment event
+            env.events().publish(
+                (symbol_short!("abandoned"),),
+                (votes, total_raised - plan.disbursed)
+            );
+        }
+
+        Ok(abandoned)
+    }
+
+    /// Deploy a milestone escrow from `escrow_wasm_hash` and move the raised funds
+    /// into it, with the project owner as client and `provider` as provider
+    /// (project owner only, once funded, instead of `claim_funds`)
+    pub fn handoff_to_escrow(
+        env: Env,
+        escrow_wasm_hash: BytesN<32>,
+        salt: BytesN<32>,
+        provider: Address,
+    ) -> Result<Address, Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funded {
+            return Err(Error::PoolNotFunded);
+        }
+
+        if env.storage().instance().has(&DataKey::FundsClaimed) {
+            return Err(Error::FundsAlreadyClaimed);
+        }
+
+        if env.storage().instance().has(&DataKey::DisbursementPlan) {
+            return Err(Error::DisbursementPlanActive);
+        }
+
+        let escrow = env.deployer().with_current_contract(salt).deploy(escrow_wasm_hash);
+        let total_raised = fund_escrow(&env, &escrow, &project_owner, &provider)?;
+
+        // Emit handoff event
+        env.events().publish(
+            (symbol_short!("escrow"),),
+            (escrow.clone(), provider, total_raised)
+        );
+
+        Ok(escrow)
+    }
+
+    /// Deposit revenue to be shared among backers pro-rata to their contributions
+    /// (project owner only, once funded)
+    pub fn deposit_revenue(env: Env, amount: i128) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funded {
+            return Err(Error::PoolNotFunded);
+        }
+
+        let total_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised)
+            .ok_or(Error::NotInitialized)?;
+
+        let per_unit: i128 = env.storage().instance().get(&DataKey::RevenuePerUnit).unwrap_or(0);
+        let per_unit = amount.checked_mul(REVENUE_PRECISION).ok_or(Error::Overflow)?
+            .checked_div(total_raised).ok_or(Error::BalanceInvariant)?
+            .checked_add(per_unit).ok_or(Error::Overflow)?;
+        let total_revenue: i128 = env.storage().instance().get(&DataKey::TotalRevenue).unwrap_or(0);
+        let total_revenue = total_revenue.checked_add(amount).ok_or(Error::Overflow)?;
+
+        let token: Address = env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        token::Client::new(&env, &token).transfer(&project_owner, &env.current_contract_address(), &amount);
+
+        env.storage().instance().set(&DataKey::RevenuePerUnit, &per_unit);
+        env.storage().instance().set(&DataKey::TotalRevenue, &total_revenue);
+
+        // Emit revenue event
+        env.events().publish(
+            (symbol_short!("revenue"),),
+            (amount, total_revenue, per_unit)
+        );
+
+        Ok(())
+    }
+
+    /// Backer claims their share of the revenue deposited so far
+    pub fn claim_revenue(env: Env, backer: Address) -> Result<i128, Error> {
+        backer.require_auth();
+
+        let amount = settle_revenue(&env, &backer)?;
+        if amount == 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        let token: Address = env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &backer, &amount);
+
+        // Emit revenue claim event
+        env.events().publish(
+            (symbol_short!("rev_claim"),),
+            (backer, amount)
+        );
+
+        Ok(amount)
+    }
+
+    /// Referrer claims their reward, paid in the tokens they referred (only while
+    /// the pool is funded). Returns the reward valued in the funding token.
+    pub fn claim_referral_reward(env: Env, referrer: Address) -> Result<i128, Error> {
+        referrer.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        // Unclaimed rewards of an abandoned project are refunded to backers
+        if status != PoolStatus::Funded {
+            return Err(Error::PoolNotFunded);
+        }
+
+        let claimed_key = ReferralKey::Claimed(referrer.clone());
+        if env.storage().persistent().has(&claimed_key) {
+            return Err(Error::FundsAlreadyClaimed);
+        }
+
+        let volume: i128 = env.storage().persistent().get(&ReferralKey::Volume(referrer.clone())).unwrap_or(0);
+        let reward = referral_reward(&env, volume)?;
+        if reward <= 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        env.storage().persistent().set(&claimed_key, &true);
+        env.storage().persistent().extend_ttl(&claimed_key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+        for token in accepted_tokens(&env)?.iter() {
+            let referred: i128 = env.storage().persistent()
+                .get(&ReferralKey::Amount(referrer.clone(), token.clone()))
+                .unwrap_or(0);
+            let amount = referral_reward(&env, referred)?;
+            if amount > 0 {
+                let key = ReferralKey::TokenRewardsPaid(token.clone());
+                let paid: i128 = env.storage().instance().get(&key).unwrap_or(0);
+                env.storage().instance().set(&key, &paid.checked_add(amount).ok_or(Error::Overflow)?);
+                token::Client::new(&env, &token).transfer(&env.current_contract_address(), &referrer, &amount);
+            }
+        }
+
+        let paid: i128 = env.storage().instance().get(&ReferralKey::RewardsPaid).unwrap_or(0);
+        env.storage().instance().set(&ReferralKey::RewardsPaid, &paid.checked_add(reward).ok_or(Error::Overflow)?);
+
+        // Emit referral reward event
+        env.events().publish(
+            (symbol_short!("ref_claim"),),
+            (referrer, volume, reward)
+        );
+
+        Ok(reward)
+    }
+
+    /// Mark a backer's reward as fulfilled (project owner only, once funded)
+    pub fn mark_tier_fulfilled(env: Env, backer: Address) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funded {
+            return Err(Error::PoolNotFunded);
+        }
+
+        let key = DataKey::TierClaim(backer.clone());
+        let mut claim: TierClaim = env.storage().persistent().get(&key)
+            .ok_or(Error::NoTierClaim)?;
+
+        claim.fulfilled = true;
+        env.storage().persistent().set(&key, &claim);
+        env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+        // Emit fulfilment event
+        env.events().publish(
+            (symbol_short!("fulfil"),),
+            (backer, claim.tier_id)
+        );
+
+        Ok(())
+    }
+
+    /// Set the share token minted to backers (project owner only, once). The
+    /// pool must already be the token's admin and no shares may exist yet.
+    pub fn set_share_token(env: Env, share_token: Address) -> Result<(), Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
+
+        if env.storage().instance().has(&DataKey::ShareToken) {
+            return Err(Error::ShareTokenAlreadySet);
+        }
+
+        let share_client = ShareTokenClient::new(&env, &share_token);
+        if share_client.admin() != env.current_contract_address() || share_client.total_supply() != 0 {
+            return Err(Error::InvalidShareToken);
+        }
+
+        env.storage().instance().set(&DataKey::ShareToken, &share_token);
+
+        Ok(())
+    }
+
+    /// Backer claims share tokens, one share per unit contributed (only if pool funded)
+    pub fn claim_shares(env: Env, backer: Address) -> Result<i128, Error> {
+        backer.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funded {
+            return Err(Error::PoolNotFunded);
+        }
+
+        let share_token: Address = env.storage().instance().get(&DataKey::ShareToken)
+            .ok_or(Error::ShareTokenNotSet)?;
+
+        let key = DataKey::SharesClaimed(backer.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(Error::SharesAlreadyClaimed);
+        }
+
+        let contribution = contribution_of(&env, &backer);
+        if contribution <= 0 {
+            return Err(Error::NoContribution);
+        }
+
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+        ShareTokenClient::new(&env, &share_token).mint(&backer, &contribution);
+
+        // Emit share claim event
+        env.events().publish(
+            (symbol_short!("shares"),),
+            (backer, contribution)
+        );
+
+        Ok(contribution)
+    }
+
+    /// Propose moving the deadline to `new_deadline` (project owner only, while funding).
+    /// Backers vote for `voting_period` seconds, which must end by the current deadline.
+    pub fn propose_extension(
+        env: Env,
+        new_deadline: u64,
+        voting_period: u64,
+        withdrawal: ExtensionWithdrawal,
+    ) -> Result<u32, Error> {
+        let project_owner: Address = env.storage().instance().get(&DataKey::ProjectOwner)
+            .ok_or(Error::NotInitialized)?;
+
+        project_owner.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
+
+        let previous: Option<DeadlineExtension> = env.storage().instance().get(&DataKey::Extension);
+        if let Some(previous) = previous {
+            if previous.status == ExtensionStatus::Voting {
+                return Err(Error::ExtensionPending);
+            }
+        }
+
+        let deadline: u64 = env.storage().instance().get(&DataKey::Deadline)
+            .ok_or(Error::NotInitialized)?;
+        let voting_ends = env.ledger().timestamp().checked_add(voting_period).ok_or(Error::Overflow)?;
+
+        if voting_period == 0 || voting_ends > deadline || new_deadline <= deadline {
+            return Err(Error::InvalidDeadline);
+        }
+
+        let id: u32 = env.storage().instance().get(&DataKey::ExtensionCount).unwrap_or(0);
+        env.storage().instance().set(&DataKey::ExtensionCount, &id.checked_add(1).ok_or(Error::Overflow)?);
+        env.storage().instance().set(&DataKey::Extension, &DeadlineExtension {
+            id,
+            new_deadline,
+            voting_ends,
+            withdrawal,
+            votes_for: 0,
+            votes_against: 0,
+            status: ExtensionStatus::Voting,
+        });
+
+        // Emit extension proposal event
+        env.events().publish(
+            (symbol_short!("ext_prop"),),
+            (id, new_deadline, voting_ends)
+        );
+
+        Ok(id)
+    }
+
+    /// Vote on the open deadline extension, weighted by the backer's current contribution
+    pub fn vote_extension(env: Env, backer: Address, approve: bool) -> Result<(), Error> {
+        backer.require_auth();
+
+        let mut extension: DeadlineExtension = env.storage().instance().get(&DataKey::Extension)
+            .ok_or(Error::NoExtension)?;
+
+        if extension.status != ExtensionStatus::Voting || env.ledger().timestamp() >= extension.voting_ends {
+            return Err(Error::VotingClosed);
+        }
+
+        let weight = contribution_of(&env, &backer);
+        if weight <= 0 {
+            return Err(Error::NoContribution);
+        }
+
+        let key = DataKey::ExtensionVote(extension.id, backer.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(Error::AlreadyVoted);
+        }
+
+        if approve {
+            extension.votes_for = extension.votes_for.checked_add(weight).ok_or(Error::Overflow)?;
+        } else {
+            extension.votes_against = extension.votes_against.checked_add(weight).ok_or(Error::Overflow)?;
+        }
+
+        env.storage().instance().set(&DataKey::Extension, &extension);
+        env.storage().persistent().set(&key, &approve);
+        env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+        // Emit vote event
+        env.events().publish(
+            (symbol_short!("ext_vote"),),
+            (extension.id, backer, approve, weight)
+        );
+
+        Ok(())
+    }
+
+    /// Close the extension vote once the voting period is over. A strict majority
+    /// of weight in favour moves the deadline; ties and empty votes reject.
+    pub fn resolve_extension(env: Env) -> Result<ExtensionStatus, Error> {
+        let mut extension: DeadlineExtension = env.storage().instance().get(&DataKey::Extension)
+            .ok_or(Error::NoExtension)?;
+
+        if extension.status != ExtensionStatus::Voting {
+            return Err(Error::VotingClosed);
+        }
+
+        if env.ledger().timestamp() < extension.voting_ends {
+            return Err(Error::VotingNotEnded);
+        }
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        // A pool that closed early at its hard cap keeps its outcome
+        extension.status = if status == PoolStatus::Funding && extension.votes_for > extension.votes_against {
+            env.storage().instance().set(&DataKey::Deadline, &extension.new_deadline);
+            ExtensionStatus::Approved
+        } else {
+            ExtensionStatus::Rejected
+        };
+        env.storage().instance().set(&DataKey::Extension, &extension);
+
+        // Emit extension outcome event
+        env.events().publish(
+            (symbol_short!("ext_done"),),
+            (extension.id, extension.status.clone(), extension.votes_for, extension.votes_against)
+        );
+
+        Ok(extension.status)
+    }
+
+    /// Withdraw a contribution after an approved extension, before the new deadline.
+    /// Depending on the proposal, only backers who voted against or all backers may withdraw.
+    pub fn withdraw_after_extension(env: Env, backer: Address) -> Result<i128, Error> {
+        backer.require_auth();
+
+        let status: PoolStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != PoolStatus::Funding {
+            return Err(Error::PoolNotFunding);
+        }
+
+        let extension: DeadlineExtension = env.storage().instance().get(&DataKey::Extension)
+            .ok_or(Error::NoExtension)?;
+
+        if extension.status != ExtensionStatus::Approved {
+            return Err(Error::WithdrawalNotAllowed);
+        }
+
+        let deadline: u64 = env.storage().instance().get(&DataKey::Deadline)
+            .ok_or(Error::NotInitialized)?;
+        if env.ledger().timestamp() >= deadline {
+            return Err(Error::WithdrawalNotAllowed);
+        }
+
+        if extension.withdrawal == ExtensionWithdrawal::Opponents {
+            let vote: Option<bool> = env.storage().persistent().get(&DataKey::ExtensionVote(extension.id, backer.clone()));
+            if vote != Some(false) {
+                return Err(Error::WithdrawalNotAllowed);
+            }
+        }
+
+        withdraw_pledge(&env, &backer)
+    }
+
+    /// Get pool status
+    pub fn get_status(env: Env) -> Result<PoolStatus, Error> {
+        env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get total raised amount
+    pub fn get_total_raised(env: Env) -> Result<i128, Error> {
+        env.storage().instance().get(&DataKey::TotalRaised)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get funding goal
+    pub fn get_funding_goal(env: Env) -> Result<i128, Error> {
+        env.storage().instance().get(&DataKey::FundingGoal)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get funding mode
+    pub fn get_funding_mode(env: Env) -> Result<FundingMode, Error> {
+        env.storage().instance().get(&DataKey::FundingMode)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get deadline
+    pub fn get_deadline(env: Env) -> Result<u64, Error> {
+        env.storage().instance().get(&DataKey::Deadline)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get the latest deadline extension proposal, if any
+    pub fn get_extension(env: Env) -> Option<DeadlineExtension> {
+        env.storage().instance().get(&DataKey::Extension)
+    }
+
+    /// Get a backer's vote on an extension proposal, `true` meaning in favour
+    pub fn get_extension_vote(env: Env, extension_id: u32, backer: Address) -> Option<bool> {
+        env.storage().persistent().get(&DataKey::ExtensionVote(extension_id, backer))
+    }
+
+    /// Get contributor's contribution, valued in the funding token
+    pub fn get_contribution(env: Env, contributor: Address) -> Result<i128, Error> {
+        if !env.storage().instance().has(&DataKey::Initialized) {
+            return Err(Error::NotInitialized);
+        }
+
+        Ok(contribution_of(&env, &contributor))
+    }
+
+    /// Get number of distinct contributors
+    pub fn get_contributor_count(env: Env) -> Result<u32, Error> {
+        env.storage().instance().get(&DataKey::ContributorCount)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Page through contributors in order of first contribution
+    pub fn get_contributors(env: Env, start: u32, limit: u32) -> Result<Vec<Address>, Error> {
+        let count: u32 = env.storage().instance().get(&DataKey::ContributorCount)
+            .ok_or(Error::NotInitialized)?;
+
+        let mut contributors: Vec<Address> = Vec::new(&env);
+        let end = start.saturating_add(limit).min(count);
+        for index in start..end {
+            let contributor: Address = env.storage().persistent().get(&DataKey::ContributorAt(index))
+                .ok_or(Error::NotInitialized)?;
+            contributors.push_back(contributor);
+        }
+
+        Ok(contributors)
+    }
+
+    /// Get reward tiers
+    pub fn get_tiers(env: Env) -> Result<Vec<RewardTier>, Error> {
+        env.storage().instance().get(&DataKey::Tiers)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get the reward tier selected by a backer
+    pub fn get_tier_claim(env: Env, backer: Address) -> Option<TierClaim> {
+        env.storage().persistent().get(&DataKey::TierClaim(backer))
+    }
+
+    /// Get disbursement plan, if configured
+    pub fn get_disbursement_plan(env: Env) -> Option<DisbursementPlan> {
+        env.storage().instance().get(&DataKey::DisbursementPlan)
+    }
+
+    /// Get the latest tranche release request, if any
+    pub fn get_tranche_request(env: Env) -> Option<TrancheRequest> {
+        env.storage().instance().get(&DataKey::TrancheRequest)
+    }
+
+    /// Get contribution weight voting to abandon the project
+    pub fn get_abandon_votes(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::AbandonVotes).unwrap_or(0)
+    }
+
+    /// Get withdrawal policy, if configured
+    pub fn get_withdrawal_policy(env: Env) -> Option<WithdrawalPolicy> {
+        env.storage().instance().get(&DataKey::WithdrawalPolicy)
+    }
+
+    /// Get access policy
+    pub fn get_access_policy(env: Env) -> AccessPolicy {
+        env.storage().instance().get(&DataKey::AccessPolicy)
+            .unwrap_or(AccessPolicy::Open)
+    }
+
+    /// Check whether an address may currently contribute under the access policy
+    pub fn is_verified(env: Env, contributor: Address) -> bool {
+        check_access(&env, &contributor).is_ok()
+    }
+
+    /// Get contribution limits, if configured
+    pub fn get_contribution_limits(env: Env) -> Option<ContributionLimits> {
+        env.storage().instance().get(&DataKey::ContributionLimits)
+    }
+
+    /// Get how much more an address may contribute, `None` if unlimited
+    pub fn get_remaining_allowance(env: Env, contributor: Address) -> Option<i128> {
+        let limits: ContributionLimits = env.storage().instance().get(&DataKey::ContributionLimits)?;
+        if limits.max_per_contributor == 0 {
+            return None;
+        }
+
+        Some((limits.max_per_contributor - contribution_of(&env, &contributor)).max(0))
+    }
+
+    /// Get hard cap, if configured
+    pub fn get_hard_cap(env: Env) -> Option<HardCap> {
+        env.storage().instance().get(&DataKey::HardCap)
+    }
+
+    /// Get stretch goal thresholds
+    pub fn get_stretch_goals(env: Env) -> Result<Vec<i128>, Error> {
+        env.storage().instance().get(&DataKey::StretchGoals)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get number of stretch goals reached
+    pub fn get_stretch_goals_reached(env: Env) -> Result<u32, Error> {
+        env.storage().instance().get(&DataKey::StretchGoalsReached)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get share token, if configured
+    pub fn get_share_token(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::ShareToken)
+    }
+
+    /// Check whether a backer has claimed their shares
+    pub fn has_claimed_shares(env: Env, backer: Address) -> bool {
+        env.storage().persistent().has(&DataKey::SharesClaimed(backer))
+    }
+
+    /// Get the reason hash given when the campaign was cancelled, if it was
+    pub fn get_cancel_reason(env: Env) -> Option<BytesN<32>> {
+        env.storage().instance().get(&DataKey::CancelReason)
+    }
+
+    /// Get the index up to which `process_refunds` has pushed refunds
+    pub fn get_refund_cursor(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::RefundCursor).unwrap_or(0)
+    }
+
+    /// Get a refund `process_refunds` could not push to a contributor
+    pub fn get_unpaid_refund(env: Env, contributor: Address, token: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::UnpaidRefund(contributor, token)).unwrap_or(0)
+    }
+
+    /// Get total revenue deposited for backers
+    pub fn get_total_revenue(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::TotalRevenue).unwrap_or(0)
+    }
+
+    /// Get revenue a backer can currently claim
+    pub fn get_claimable_revenue(env: Env, backer: Address) -> Result<i128, Error> {
+        let (earned, claimed) = revenue_of(&env, &backer)?;
+        Ok(earned - claimed)
+    }
+
+    /// Get the escrow the raised funds were handed off to, if any
+    pub fn get_escrow(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Escrow)
+    }
+
+    /// Get the referral reward in bps, 0 if none
+    pub fn get_referral_reward_bps(env: Env) -> u32 {
+        env.storage().instance().get(&ReferralKey::RewardBps).unwrap_or(0)
+    }
+
+    /// Get the referrer a contributor is attributed to, if any
+    pub fn get_referrer(env: Env, contributor: Address) -> Option<Address> {
+        env.storage().persistent().get(&ReferralKey::Referrer(contributor))
+    }
+
+    /// Get the volume a referrer has referred, valued in the funding token
+    pub fn get_referred_volume(env: Env, referrer: Address) -> i128 {
+        env.storage().persistent().get(&ReferralKey::Volume(referrer)).unwrap_or(0)
+    }
+
+    /// Check whether a referrer has claimed their reward
+    pub fn has_claimed_referral_reward(env: Env, referrer: Address) -> bool {
+        env.storage().persistent().has(&ReferralKey::Claimed(referrer))
+    }
+
+    /// Get accepted tokens, funding token first
+    pub fn get_accepted_tokens(env: Env) -> Result<Vec<Address>, Error> {
+        accepted_tokens(&env)
+    }
+
+    /// Get price oracle, if configured
+    pub fn get_price_oracle(env: Env) -> Option<PriceOracle> {
+        env.storage().instance().get(&DataKey::PriceOracle)
+    }
+
+    /// Get raw amount raised in a token
+    pub fn get_token_raised(env: Env, token: Address) -> i128 {
+        token_raised(&env, &token)
+    }
+
+    /// Get raw amount a contributor has contributed in a token
+    pub fn get_token_contribution(env: Env, contributor: Address, token: Address) -> i128 {
+        token_contribution_of(&env, &contributor, &token)
+    }
+
+    /// Get funding token
+    pub fn get_token(env: Env) -> Result<Address, Error> {
+        env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get project owner
+    pub fn get_project_owner(env: Env) -> Result<Address, Error> {
         env.storage().instance().get(&DataKey::ProjectOwner)
             .ok_or(Error::NotInitialized)
     }
 }
+
+// Contributor's recorded total, zero if they never contributed
+fn contribution_of(env: &Env, contributor: &Address) -> i128 {
+    env.storage().persistent().get(&DataKey::Contribution(contributor.clone())).unwrap_or(0)
+}
+
+fn set_contribution(env: &Env, contributor: &Address, amount: i128) {
+    let key = DataKey::Contribution(contributor.clone());
+    env.storage().persistent().set(&key, &amount);
+    env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+}
+
+// Funding token followed by any other accepted tokens
+fn accepted_tokens(env: &Env) -> Result<Vec<Address>, Error> {
+    env.storage().instance().get(&DataKey::Tokens)
+        .ok_or(Error::NotInitialized)
+}
+
+fn token_raised(env: &Env, token: &Address) -> i128 {
+    env.storage().instance().get(&DataKey::TokenRaised(token.clone())).unwrap_or(0)
+}
+
+fn token_contribution_of(env: &Env, contributor: &Address, token: &Address) -> i128 {
+    env.storage().persistent()
+        .get(&DataKey::TokenContribution(contributor.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+fn set_token_contribution(env: &Env, contributor: &Address, token: &Address, amount: i128) {
+    let key = DataKey::TokenContribution(contributor.clone(), token.clone());
+    env.storage().persistent().set(&key, &amount);
+    env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+}
+
+// Raw amount of a token available to the project owner: everything raised
+// less the reserve owed to referrers
+fn payable_raised(env: &Env, token: &Address) -> Result<i128, Error> {
+    let referred: i128 = env.storage().instance().get(&ReferralKey::TokenAmount(token.clone())).unwrap_or(0);
+    Ok(token_raised(env, token) - referral_reward(env, referred)?)
+}
+
+// Total raised less the referral reserve, valued in the funding token
+fn payable_value(env: &Env) -> Result<i128, Error> {
+    let total_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised)
+        .ok_or(Error::NotInitialized)?;
+    let referred: i128 = env.storage().instance().get(&ReferralKey::TotalVolume).unwrap_or(0);
+    Ok(total_raised - referral_reward(env, referred)?)
+}
+
+// Referral reward owed on a referred amount
+fn referral_reward(env: &Env, referred: i128) -> Result<i128, Error> {
+    let bps: u32 = env.storage().instance().get(&ReferralKey::RewardBps).unwrap_or(0);
+    Ok(referred.checked_mul(bps as i128).ok_or(Error::Overflow)? / BPS_DENOMINATOR)
+}
+
+// Adjust the raw amount of a token referred by a referrer, and in total
+fn add_referred_amount(env: &Env, referrer: &Address, token: &Address, amount: i128) -> Result<(), Error> {
+    let key = ReferralKey::Amount(referrer.clone(), token.clone());
+    let referred: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    env.storage().persistent().set(&key, &referred.checked_add(amount).ok_or(Error::Overflow)?);
+    env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+    let key = ReferralKey::TokenAmount(token.clone());
+    let referred: i128 = env.storage().instance().get(&key).unwrap_or(0);
+    env.storage().instance().set(&key, &referred.checked_add(amount).ok_or(Error::Overflow)?);
+
+    Ok(())
+}
+
+// Adjust the value referred by a referrer, and in total
+fn add_referred_volume(env: &Env, referrer: &Address, value: i128) -> Result<(), Error> {
+    let key = ReferralKey::Volume(referrer.clone());
+    let volume: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    env.storage().persistent().set(&key, &volume.checked_add(value).ok_or(Error::Overflow)?);
+    env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+    let volume: i128 = env.storage().instance().get(&ReferralKey::TotalVolume).unwrap_or(0);
+    env.storage().instance().set(&ReferralKey::TotalVolume, &volume.checked_add(value).ok_or(Error::Overflow)?);
+
+    Ok(())
+}
+
+// Value an amount of an accepted token in the funding token's unit, using the
+// oracle's prices for both tokens and adjusting for their decimals
+fn value_of(env: &Env, token: &Address, amount: i128) -> Result<i128, Error> {
+    let funding_token: Address = env.storage().instance().get(&DataKey::Token)
+        .ok_or(Error::NotInitialized)?;
+    if *token == funding_token {
+        return Ok(amount);
+    }
+
+    if !accepted_tokens(env)?.contains(token) {
+        return Err(Error::TokenNotAccepted);
+    }
+
+    let oracle: PriceOracle = env.storage().instance().get(&DataKey::PriceOracle)
+        .ok_or(Error::OracleNotSet)?;
+    let token_price = price_of(env, &oracle, token)?;
+    let funding_price = price_of(env, &oracle, &funding_token)?;
+
+    let token_decimals = token::Client::new(env, token).decimals();
+    let funding_decimals = token::Client::new(env, &funding_token).decimals();
+
+    let mut value = amount.checked_mul(token_price).ok_or(Error::Overflow)?;
+    if funding_decimals >= token_decimals {
+        let scale = 10i128.checked_pow(funding_decimals - token_decimals).ok_or(Error::Overflow)?;
+        value = value.checked_mul(scale).ok_or(Error::Overflow)?;
+    } else {
+        let scale = 10i128.checked_pow(token_decimals - funding_decimals).ok_or(Error::Overflow)?;
+        value /= scale;
+    }
+
+    Ok(value / funding_price)
+}
+
+// Latest oracle price for a token, rejecting missing or stale prices
+fn price_of(env: &Env, oracle: &PriceOracle, token: &Address) -> Result<i128, Error> {
+    let data = PriceOracleClient::new(env, &oracle.oracle)
+        .lastprice(&Asset::Stellar(token.clone()))
+        .ok_or(Error::PriceUnavailable)?;
+
+    if data.price <= 0 {
+        return Err(Error::PriceUnavailable);
+    }
+
+    if env.ledger().timestamp().saturating_sub(data.timestamp) > oracle.max_price_age {
+        return Err(Error::StalePrice);
+    }
+
+    Ok(data.price)
+}
+
+// Record a backer's tier selection, enforcing the pledge minimum and stock.
+// Re-selecting the backer's current tier is a no-op.
+fn select_tier(env: &Env, contributor: &Address, tier_id: u32, pledged: i128) -> Result<(), Error> {
+    let mut tiers: Vec<RewardTier> = env.storage().instance().get(&DataKey::Tiers)
+        .ok_or(Error::NotInitialized)?;
+    let mut tier = tiers.get(tier_id).ok_or(Error::TierNotFound)?;
+
+    let key = DataKey::TierClaim(contributor.clone());
+    let existing: Option<TierClaim> = env.storage().persistent().get(&key);
+    if let Some(claim) = existing {
+        if claim.tier_id == tier_id {
+            return Ok(());
+        }
+        return Err(Error::TierAlreadySelected);
+    }
+
+    if pledged < tier.min_pledge {
+        return Err(Error::BelowTierMinimum);
+    }
+
+    if tier.limit > 0 && tier.claimed >= tier.limit {
+        return Err(Error::TierSoldOut);
+    }
+
+    tier.claimed = tier.claimed.checked_add(1).ok_or(Error::Overflow)?;
+    tiers.set(tier_id, tier);
+    env.storage().instance().set(&DataKey::Tiers, &tiers);
+
+    env.storage().persistent().set(&key, &TierClaim { tier_id, fulfilled: false });
+    env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+    // Emit tier selection event
+    env.events().publish(
+        (symbol_short!("tier_sel"),),
+        (contributor.clone(), tier_id)
+    );
+
+    Ok(())
+}
+
+// Initialize a fresh milestone escrow and deposit everything raised into it,
+// one deposit per accepted token
+fn fund_escrow(env: &Env, escrow: &Address, project_owner: &Address, provider: &Address) -> Result<i128, Error> {
+    let total_raised = payable_value(env)?;
+
+    env.storage().instance().set(&DataKey::FundsClaimed, &true);
+    env.storage().instance().set(&DataKey::Escrow, escrow);
+
+    let escrow_client = EscrowClient::new(env, escrow);
+    escrow_client.initialize(project_owner, provider, &true);
+
+    let contract = env.current_contract_address();
+    for token in accepted_tokens(env)?.iter() {
+        let amount = payable_raised(env, &token)?;
+        if amount <= 0 {
+            continue;
+        }
+
+        escrow_client.add_token(&token);
+
+        // Authorize the escrow to pull the raised funds from this contract
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token.clone(),
+                    fn_name: Symbol::new(env, "transfer"),
+                    args: (contract.clone(), escrow.clone(), amount).into_val(env),
+                },
+                sub_invocations: vec![env],
+            }),
+        ]);
+
+        escrow_client.deposit(&contract, &token, &amount);
+    }
+
+    Ok(total_raised)
+}
+
+// Revenue earned by a backer's contribution so far, and the part already paid out
+fn revenue_of(env: &Env, backer: &Address) -> Result<(i128, i128), Error> {
+    let per_unit: i128 = env.storage().instance().get(&DataKey::RevenuePerUnit).unwrap_or(0);
+    let earned = contribution_of(env, backer).checked_mul(per_unit).ok_or(Error::Overflow)? / REVENUE_PRECISION;
+    let claimed: i128 = env.storage().persistent().get(&DataKey::RevenueClaimed(backer.clone())).unwrap_or(0);
+
+    if claimed > earned {
+        return Err(Error::BalanceInvariant);
+    }
+
+    Ok((earned, claimed))
+}
+
+// Mark a backer's pending revenue as paid and return it; the caller transfers it
+fn settle_revenue(env: &Env, backer: &Address) -> Result<i128, Error> {
+    let (earned, claimed) = revenue_of(env, backer)?;
+    if earned == claimed {
+        return Ok(0);
+    }
+
+    let key = DataKey::RevenueClaimed(backer.clone());
+    env.storage().persistent().set(&key, &earned);
+    env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+    Ok(earned - claimed)
+}
+
+// Refund a contributor of a failed pool in full, or of an abandoned pool
+// pro-rata to the funds neither disbursed nor claimed by referrers, along with
+// any revenue owed. With `defer_failed`, transfers that fail are recorded as
+// unpaid refunds rather than reverting.
+fn refund_contributor(
+    env: &Env,
+    status: &PoolStatus,
+    contributor: &Address,
+    contribution: i128,
+    defer_failed: bool,
+) -> Result<i128, Error> {
+    let total_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised)
+        .ok_or(Error::NotInitialized)?;
+    if contribution < 0 || contribution > total_raised {
+        return Err(Error::BalanceInvariant);
+    }
+
+    let abandoned = *status == PoolStatus::Abandoned;
+    let refund = if abandoned {
+        let plan: DisbursementPlan = env.storage().instance().get(&DataKey::DisbursementPlan)
+            .ok_or(Error::NoDisbursementPlan)?;
+        let rewards_paid: i128 = env.storage().instance().get(&ReferralKey::RewardsPaid).unwrap_or(0);
+        let unreleased = total_raised
+            .checked_sub(rewards_paid)
+            .and_then(|rest| rest.checked_sub(plan.disbursed))
+            .ok_or(Error::Overflow)?;
+        contribution.checked_mul(unreleased).ok_or(Error::Overflow)? / total_raised
+    } else {
+        contribution
+    };
+
+    // Revenue shared before the project was abandoned is paid out with the refund
+    let revenue = settle_revenue(env, contributor)?;
+
+    // Zero the contributor's entry, keeping their index position
+    set_contribution(env, contributor, 0);
+    env.storage().persistent().remove(&DataKey::RevenueClaimed(contributor.clone()));
+
+    // Return each token contributed, less its share of any tranches and referral rewards paid out
+    let funding_token: Address = env.storage().instance().get(&DataKey::Token)
+        .ok_or(Error::NotInitialized)?;
+    for token in accepted_tokens(env)?.iter() {
+        let mut amount = token_contribution_of(env, contributor, &token);
+        if amount > 0 {
+            set_token_contribution(env, contributor, &token, 0);
+            if abandoned {
+                let raised = token_raised(env, &token);
+                let unreleased = raised
+                    - env.storage().instance().get::<_, i128>(&ReferralKey::TokenRewardsPaid(token.clone())).unwrap_or(0)
+                    - env.storage().instance().get::<_, i128>(&DataKey::TokenDisbursed(token.clone())).unwrap_or(0);
+                amount = amount.checked_mul(unreleased).ok_or(Error::Overflow)? / raised;
+            }
+        }
+        if token == funding_token {
+            amount = amount.checked_add(revenue).ok_or(Error::Overflow)?;
+        }
+        if amount > 0 {
+            if defer_failed {
+                push_refund(env, &token, contributor, amount)?;
+            } else {
+                token::Client::new(env, &token).transfer(&env.current_contract_address(), contributor, &amount);
+            }
+        }
+    }
+
+    let refund = refund.checked_add(revenue).ok_or(Error::Overflow)?;
+
+    // Emit refund event
+    env.events().publish(
+        (symbol_short!("refund"),),
+        (contributor.clone(), refund)
+    );
+
+    Ok(refund)
+}
+
+// Push a refund transfer, recording it as unpaid for the contributor to claim
+// later if the transfer fails
+fn push_refund(env: &Env, token: &Address, contributor: &Address, amount: i128) -> Result<(), Error> {
+    let result = token::Client::new(env, token).try_transfer(&env.current_contract_address(), contributor, &amount);
+    if matches!(result, Ok(Ok(()))) {
+        return Ok(());
+    }
+
+    let key = DataKey::UnpaidRefund(contributor.clone(), token.clone());
+    let unpaid: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    env.storage().persistent().set(&key, &unpaid.checked_add(amount).ok_or(Error::Overflow)?);
+    env.storage().persistent().extend_ttl(&key, CONTRIBUTOR_TTL_THRESHOLD, CONTRIBUTOR_TTL_EXTEND_TO);
+
+    // Emit failed refund event
+    env.events().publish(
+        (symbol_short!("ref_fail"),),
+        (contributor.clone(), token.clone(), amount)
+    );
+
+    Ok(())
+}
+
+// Return a backer's whole contribution while the pool is still funding,
+// releasing their reward tier so the slot can be claimed again
+fn withdraw_pledge(env: &Env, contributor: &Address) -> Result<i128, Error> {
+    let contribution = contribution_of(env, contributor);
+    if contribution <= 0 {
+        return Err(Error::NoContribution);
+    }
+
+    let total_raised: i128 = env.storage().instance().get(&DataKey::TotalRaised)
+        .ok_or(Error::NotInitialized)?;
+    if contribution > total_raised {
+        return Err(Error::BalanceInvariant);
+    }
+
+    let total_raised = total_raised - contribution;
+    env.storage().instance().set(&DataKey::TotalRaised, &total_raised);
+    set_contribution(env, contributor, 0);
+
+    let referrer: Option<Address> = env.storage().persistent().get(&ReferralKey::Referrer(contributor.clone()));
+
+    let claim_key = DataKey::TierClaim(contributor.clone());
+    let claim: Option<TierClaim> = env.storage().persistent().get(&claim_key);
+    if let Some(claim) = claim {
+        let mut tiers: Vec<RewardTier> = env.storage().instance().get(&DataKey::Tiers)
+            .ok_or(Error::NotInitialized)?;
+        if let Some(mut tier) = tiers.get(claim.tier_id) {
+            tier.claimed = tier.claimed.saturating_sub(1);
+            tiers.set(claim.tier_id, tier);
+            env.storage().instance().set(&DataKey::Tiers, &tiers);
+        }
+        env.storage().persistent().remove(&claim_key);
+    }
+
+    for token in accepted_tokens(env)?.iter() {
+        let amount = token_contribution_of(env, contributor, &token);
+        if amount <= 0 {
+            continue;
+        }
+
+        let raised = token_raised(env, &token).checked_sub(amount).ok_or(Error::Overflow)?;
+        if raised < 0 {
+            return Err(Error::BalanceInvariant);
+        }
+        env.storage().instance().set(&DataKey::TokenRaised(token.clone()), &raised);
+        set_token_contribution(env, contributor, &token, 0);
+        if let Some(referrer) = &referrer {
+            add_referred_amount(env, referrer, &token, -amount)?;
+        }
+        token::Client::new(env, &token).transfer(&env.current_contract_address(), contributor, &amount);
+    }
+
+    if let Some(referrer) = &referrer {
+        add_referred_volume(env, referrer, -contribution)?;
+    }
+
+    // Emit withdrawal event
+    env.events().publish(
+        (symbol_short!("withdraw"),),
+        (contributor.clone(), contribution, total_raised)
+    );
+
+    Ok(contribution)
+}
+
+// Gated pools require a current, unexpired grant for the active policy
+fn check_access(env: &Env, contributor: &Address) -> Result<(), Error> {
+    let policy: AccessPolicy = env.storage().instance().get(&DataKey::AccessPolicy)
+        .unwrap_or(AccessPolicy::Open);
+    if policy == AccessPolicy::Open {
+        return Ok(());
+    }
+
+    let grant: AccessGrant = env.storage().persistent().get(&DataKey::AccessGrant(contributor.clone()))
+        .ok_or(Error::NotVerified)?;
+    let policy_version: u32 = env.storage().instance().get(&DataKey::AccessPolicyVersion).unwrap_or(0);
+
+    if grant.policy_version != policy_version {
+        return Err(Error::NotVerified);
+    }
+
+    if grant.expires_at <= env.ledger().timestamp() {
+        return Err(Error::AttestationExpired);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test;
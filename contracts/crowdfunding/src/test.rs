@@ -0,0 +1,120 @@
+extern crate std;
+
+use super::*;
+use mock_oracle_contract::{Asset as OracleAsset, MockOracle, MockOracleClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::StellarAssetClient,
+    Env,
+};
+
+// Prices are reported with 14 decimals; EUR trades at 1.1 of the funding token
+const FUNDING_PRICE: i128 = 100_000_000_000_000;
+const EUR_PRICE: i128 = 110_000_000_000_000;
+const MAX_PRICE_AGE: u64 = 300;
+
+struct Setup<'a> {
+    env: Env,
+    pool: PoolContractClient<'a>,
+    oracle: MockOracleClient<'a>,
+    oracle_id: Address,
+    token: Address,
+    eur: Address,
+}
+
+fn setup<'a>(goal: i128) -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let eur = env.register_stellar_asset_contract_v2(admin.clone()).address();
+
+    let owner = Address::generate(&env);
+    let pool_id = env.register_contract(None, PoolContract);
+    let pool = PoolContractClient::new(&env, &pool_id);
+    pool.initialize(&owner, &token, &goal, &1000, &FundingMode::AllOrNothing);
+
+    let oracle_id = env.register_contract(None, MockOracle);
+    let oracle = MockOracleClient::new(&env, &oracle_id);
+    oracle.initialize(&admin, &14);
+    oracle.set_price(&OracleAsset::Stellar(token.clone()), &FUNDING_PRICE, &0);
+    oracle.set_price(&OracleAsset::Stellar(eur.clone()), &EUR_PRICE, &0);
+
+    Setup { env, pool, oracle, oracle_id, token, eur }
+}
+
+fn backer(s: &Setup, token: &Address, amount: i128) -> Address {
+    let backer = Address::generate(&s.env);
+    StellarAssetClient::new(&s.env, token).mint(&backer, &amount);
+    backer
+}
+
+#[test]
+fn test_contributions_are_valued_through_oracle() {
+    let s = setup(300);
+
+    assert_eq!(s.pool.try_add_accepted_token(&s.eur), Err(Ok(Error::OracleNotSet)));
+    s.pool.set_price_oracle(&s.oracle_id, &MAX_PRICE_AGE);
+    s.pool.add_accepted_token(&s.eur);
+
+    let a = backer(&s, &s.token, 100);
+    let b = backer(&s, &s.eur, 1000);
+
+    s.pool.contribute(&a, &s.token, &100, &None, &None);
+    assert_eq!(s.pool.contribute(&b, &s.eur, &100, &None, &None), 100);
+
+    assert_eq!(s.pool.get_contribution(&b), 110);
+    assert_eq!(s.pool.get_token_contribution(&b, &s.eur), 100);
+    assert_eq!(s.pool.get_token_raised(&s.eur), 100);
+    assert_eq!(s.pool.get_total_raised(), 210);
+
+    let stranger = s.env.register_stellar_asset_contract_v2(Address::generate(&s.env)).address();
+    assert_eq!(
+        s.pool.try_contribute(&b, &stranger, &10, &None, &None),
+        Err(Ok(Error::TokenNotAccepted))
+    );
+}
+
+#[test]
+fn test_stale_prices_are_rejected() {
+    let s = setup(300);
+    s.pool.set_price_oracle(&s.oracle_id, &MAX_PRICE_AGE);
+    s.pool.add_accepted_token(&s.eur);
+
+    let b = backer(&s, &s.eur, 1000);
+    s.env.ledger().set_timestamp(MAX_PRICE_AGE);
+    assert_eq!(s.pool.contribute(&b, &s.eur, &10, &None, &None), 10);
+
+    s.env.ledger().set_timestamp(MAX_PRICE_AGE + 1);
+    assert_eq!(
+        s.pool.try_contribute(&b, &s.eur, &10, &None, &None),
+        Err(Ok(Error::StalePrice))
+    );
+
+    // Both the contributed token and the funding token need a fresh price
+    s.oracle.set_price(&OracleAsset::Stellar(s.eur.clone()), &EUR_PRICE, &(MAX_PRICE_AGE + 1));
+    assert_eq!(
+        s.pool.try_contribute(&b, &s.eur, &10, &None, &None),
+        Err(Ok(Error::StalePrice))
+    );
+
+    s.oracle.set_price(&OracleAsset::Stellar(s.token.clone()), &FUNDING_PRICE, &(MAX_PRICE_AGE + 1));
+    assert_eq!(s.pool.contribute(&b, &s.eur, &10, &None, &None), 10);
+    assert_eq!(s.pool.get_contribution(&b), 22);
+}
+
+#[test]
+fn test_missing_price_is_rejected() {
+    let s = setup(300);
+    s.pool.set_price_oracle(&s.oracle_id, &MAX_PRICE_AGE);
+
+    let gbp = s.env.register_stellar_asset_contract_v2(Address::generate(&s.env)).address();
+    s.pool.add_accepted_token(&gbp);
+
+    let b = backer(&s, &gbp, 100);
+    assert_eq!(
+        s.pool.try_contribute(&b, &gbp, &10, &None, &None),
+        Err(Ok(Error::PriceUnavailable))
+    );
+}
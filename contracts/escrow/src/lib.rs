@@ -1,6 +1,10 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracterror, contracttype, Address, Env, Vec, Map, symbol_short};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractclient, contractimpl, contracterror, contracttype, token, vec, Address, Env, IntoVal, Symbol, Vec,
+    symbol_short,
+};
 
 // Contract errors
 #[contracterror]
@@ -17,6 +21,15 @@ pub enum Error {
     InsufficientFunds = 8,
     DisputeActive = 9,
     NoDisputeActive = 10,
+    VaultNotConfigured = 11,
+    VaultPositionOpen = 12,
+    InvalidYieldSplit = 13,
+    Overflow = 14,
+    EscrowNotActive = 15,
+    BalanceInvariant = 16,
+    TokenNotAccepted = 17,
+    TooManyTokens = 18,
+    VaultTokenMismatch = 19,
 }
 
 // Escrow status enum
@@ -34,6 +47,7 @@ pub enum EscrowStatus {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Milestone {
     pub id: u32,
+    pub token: Address,
     pub amount: i128,
     pub completed: bool,
 }
@@ -43,25 +57,150 @@ pub struct Milestone {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TimeRelease {
     pub release_time: u64,
+    pub token: Address,
     pub amount: i128,
     pub released: bool,
 }
 
+// Yield vault configured for a token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VaultConfig {
+    pub vault: Address,
+    pub provider_share_bps: u32, // Provider's share of yield, rest goes to the client
+}
+
+// Unreleased funds currently deposited in a vault
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VaultPosition {
+    pub shares: i128,
+    pub principal: i128,
+}
+
+// Per-token balances in the escrow summary
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenBalance {
+    pub token: Address,
+    pub deposited: i128,
+    pub released: i128,
+    pub withdrawn: i128,
+    pub refunded: i128,
+    pub yielded: i128,   // Provider's share of vault yield
+    pub lost: i128,      // Principal lost in the yield vault
+    pub available: i128, // Released but not yet withdrawn
+    pub remaining: i128, // Deposited but neither released, refunded nor lost
+}
+
+// Next step needed to move the escrow forward
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NextAction {
+    None,
+    ResolveDispute,
+    Withdraw(Address),          // Token with a withdrawable balance
+    CompleteMilestone(u32),     // Milestone id
+    ReleaseTimeBased(u32, u64), // Schedule index and release time
+}
+
+// Full escrow summary returned by `get_escrow`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowInfo {
+    pub client: Address,
+    pub provider: Address,
+    pub status: EscrowStatus,
+    pub release_type: bool,
+    pub created_at: u64,
+    pub balances: Vec<TokenBalance>,
+    pub milestones: Vec<Milestone>,
+    pub time_schedule: Vec<TimeRelease>,
+    pub next_action: NextAction,
+}
+
+// Kind of movement recorded in the payout ledger
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PayoutKind {
+    Released,  // Credited to the provider's withdrawable balance
+    Withdrawn, // Paid out to the provider
+    Refunded,  // Returned to the client
+    YieldPaid, // Client's share of vault yield
+    VaultLoss, // Principal the vault failed to return
+}
+
+// What triggered a payout ledger entry
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PayoutSource {
+    None,
+    Milestone(u32),   // Milestone id
+    TimeRelease(u32), // Schedule index
+    Dispute,
+    VaultYield,
+}
+
+// Payout ledger entry
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutRecord {
+    pub kind: PayoutKind,
+    pub source: PayoutSource,
+    pub token: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
 // Storage keys
 #[contracttype]
 pub enum DataKey {
     Client,
     Provider,
-    TotalAmount,
-    ReleasedAmount,
+    Arbiter,                 // Resolves disputes once agreed by both parties
+    Tokens,                  // SEP-41 tokens registered by the client
+    TotalAmount(Address),    // Deposited per token
+    ReleasedAmount(Address), // Released per token, including withdrawn funds
+    WithdrawnAmount(Address),
+    RefundedAmount(Address),
+    YieldAmount(Address),    // Provider's vault yield per token
+    LostAmount(Address),     // Vault losses per token
+    Vault(Address),          // VaultConfig per token
+    VaultPosition(Address),  // VaultPosition per token
     Status,
     ReleaseType, // "milestone" or "time"
     Milestones,
     TimeSchedule,
     CreatedAt,
     Initialized,
+    PayoutCount,
+    Payout(u32), // PayoutRecord, persistent
+}
+
+/// Interface a yield vault must implement to hold idle escrow funds.
+/// Each vault accepts a single underlying SEP-41 token.
+#[contractclient(name = "VaultClient")]
+pub trait VaultInterface {
+    /// Pull `amount` of the underlying token from `from` and return the shares minted
+    fn deposit(env: Env, from: Address, amount: i128) -> i128;
+
+    /// Burn `shares` held by `owner` and return the underlying amount paid to them
+    fn redeem(env: Env, owner: Address, shares: i128) -> i128;
+
+    /// Underlying token accepted by the vault
+    fn token(env: Env) -> Address;
 }
 
+const BPS_DENOMINATOR: i128 = 10_000;
+
+// Maximum number of tokens an escrow can hold
+const MAX_TOKENS: u32 = 5;
+
+// Payout ledger entries live in persistent storage and are kept alive for
+// roughly 180 days whenever they drop below 30 days of remaining TTL
+const PAYOUT_TTL_THRESHOLD: u32 = 518_400;
+const PAYOUT_TTL_EXTEND_TO: u32 = 3_110_400;
+
 #[contract]
 pub struct EscrowContract;
 
@@ -72,7 +211,6 @@ impl EscrowContract {
         env: Env,
         client: Address,
         provider: Address,
-        total_amount: i128,
         release_type: bool, // true for milestone-based, false for time-based
     ) -> Result<(), Error> {
         // Check if already initialized
@@ -80,41 +218,87 @@ impl EscrowContract {
             return Err(Error::AlreadyInitialized);
         }
 
-        // Validate inputs
-        if total_amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
-
         // Require authorization from client
         client.require_auth();
 
         // Store escrow data
         env.storage().instance().set(&DataKey::Client, &client);
         env.storage().instance().set(&DataKey::Provider, &provider);
-        env.storage().instance().set(&DataKey::TotalAmount, &total_amount);
-        env.storage().instance().set(&DataKey::ReleasedAmount, &0i128);
         env.storage().instance().set(&DataKey::Status, &EscrowStatus::Active);
         env.storage().instance().set(&DataKey::ReleaseType, &release_type);
         env.storage().instance().set(&DataKey::CreatedAt, &env.ledger().timestamp());
         env.storage().instance().set(&DataKey::Initialized, &true);
+        env.storage().instance().set(&DataKey::PayoutCount, &0u32);
 
-        // Initialize empty milestone and time schedule vectors
+        // Initialize empty token list, milestone and time schedule vectors
+        let tokens: Vec<Address> = Vec::new(&env);
         let milestones: Vec<Milestone> = Vec::new(&env);
         let time_schedule: Vec<TimeRelease> = Vec::new(&env);
+        env.storage().instance().set(&DataKey::Tokens, &tokens);
         env.storage().instance().set(&DataKey::Milestones, &milestones);
         env.storage().instance().set(&DataKey::TimeSchedule, &time_schedule);
 
         // Emit initialization event
         env.events().publish(
             (symbol_short!("init"),),
-            (client, provider, total_amount)
+            (client, provider)
+        );
+
+        Ok(())
+    }
+
+    /// Register a SEP-41 token the escrow accepts (client only)
+    pub fn add_token(env: Env, token: Address) -> Result<(), Error> {
+        let client: Address = env.storage().instance().get(&DataKey::Client)
+            .ok_or(Error::NotInitialized)?;
+
+        client.require_auth();
+
+        register_token(&env, &token)
+    }
+
+    /// Deposit a registered SEP-41 token into the escrow
+    pub fn deposit(env: Env, from: Address, token: Address, amount: i128) -> Result<(), Error> {
+        from.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let status: EscrowStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status == EscrowStatus::Completed || status == EscrowStatus::Cancelled {
+            return Err(Error::EscrowNotActive);
+        }
+
+        let tokens: Vec<Address> = env.storage().instance()
+            .get(&DataKey::Tokens)
+            .ok_or(Error::NotInitialized)?;
+
+        if !tokens.contains(&token) {
+            return Err(Error::TokenNotAccepted);
+        }
+
+        token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &amount);
+
+        let total = get_balance(&env, &DataKey::TotalAmount(token.clone()))
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+        env.storage().instance().set(&DataKey::TotalAmount(token.clone()), &total);
+        check_balances(&env, &token)?;
+
+        // Emit deposit event
+        env.events().publish(
+            (symbol_short!("deposit"),),
+            (from, token, amount)
         );
 
         Ok(())
     }
 
     /// Add a milestone
-    pub fn add_milestone(env: Env, milestone_id: u32, amount: i128) -> Result<(), Error> {
+    pub fn add_milestone(env: Env, milestone_id: u32, token: Address, amount: i128) -> Result<(), Error> {
         let client: Address = env.storage().instance().get(&DataKey::Client)
             .ok_or(Error::NotInitialized)?;
         
@@ -124,12 +308,15 @@ impl EscrowContract {
             return Err(Error::InvalidAmount);
         }
 
+        register_token(&env, &token)?;
+
         let mut milestones: Vec<Milestone> = env.storage().instance()
             .get(&DataKey::Milestones)
             .ok_or(Error::NotInitialized)?;
 
         milestones.push_back(Milestone {
             id: milestone_id,
+            token,
             amount,
             completed: false,
         });
@@ -140,7 +327,7 @@ impl EscrowContract {
     }
 
     /// Add time-based release
-    pub fn add_time_release(env: Env, release_time: u64, amount: i128) -> Result<(), Error> {
+    pub fn add_time_release(env: Env, release_time: u64, token: Address, amount: i128) -> Result<(), Error> {
         let client: Address = env.storage().instance().get(&DataKey::Client)
             .ok_or(Error::NotInitialized)?;
         
@@ -150,12 +337,15 @@ impl EscrowContract {
             return Err(Error::InvalidAmount);
         }
 
+        register_token(&env, &token)?;
+
         let mut schedule: Vec<TimeRelease> = env.storage().instance()
             .get(&DataKey::TimeSchedule)
             .ok_or(Error::NotInitialized)?;
 
         schedule.push_back(TimeRelease {
             release_time,
+            token,
             amount,
             released: false,
         });
@@ -165,6 +355,122 @@ impl EscrowContract {
         Ok(())
     }
 
+    /// Configure the yield vault used for idle funds of a token (client and
+    /// provider together, since a vault loss reduces what the provider is owed)
+    pub fn set_vault(env: Env, token: Address, vault: Address, provider_share_bps: u32) -> Result<(), Error> {
+        let client: Address = env.storage().instance().get(&DataKey::Client)
+            .ok_or(Error::NotInitialized)?;
+        let provider: Address = env.storage().instance().get(&DataKey::Provider)
+            .ok_or(Error::NotInitialized)?;
+
+        client.require_auth();
+        provider.require_auth();
+
+        if provider_share_bps as i128 > BPS_DENOMINATOR {
+            return Err(Error::InvalidYieldSplit);
+        }
+
+        if env.storage().instance().has(&DataKey::VaultPosition(token.clone())) {
+            return Err(Error::VaultPositionOpen);
+        }
+
+        if VaultClient::new(&env, &vault).token() != token {
+            return Err(Error::VaultTokenMismatch);
+        }
+
+        env.storage().instance().set(
+            &DataKey::Vault(token.clone()),
+            &VaultConfig { vault: vault.clone(), provider_share_bps },
+        );
+
+        // Emit vault configuration event
+        env.events().publish(
+            (symbol_short!("vault"),),
+            (token, vault, provider_share_bps)
+        );
+
+        Ok(())
+    }
+
+    /// Deposit unreleased funds of a token into its vault to earn yield
+    pub fn deposit_to_vault(env: Env, token: Address, amount: i128) -> Result<i128, Error> {
+        let client: Address = env.storage().instance().get(&DataKey::Client)
+            .ok_or(Error::NotInitialized)?;
+        
+        client.require_auth();
+
+        let status: EscrowStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status == EscrowStatus::Disputed {
+            return Err(Error::DisputeActive);
+        }
+        if status != EscrowStatus::Active {
+            return Err(Error::EscrowNotActive);
+        }
+
+        let config: VaultConfig = env.storage().instance().get(&DataKey::Vault(token.clone()))
+            .ok_or(Error::VaultNotConfigured)?;
+
+        let mut position: VaultPosition = env.storage().instance()
+            .get(&DataKey::VaultPosition(token.clone()))
+            .unwrap_or(VaultPosition { shares: 0, principal: 0 });
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let idle = unreleased(&env, &token)?
+            .checked_sub(position.principal)
+            .ok_or(Error::Overflow)?;
+        if amount > idle {
+            return Err(Error::InsufficientFunds);
+        }
+
+        // Authorize the vault to pull the tokens from this contract
+        let contract = env.current_contract_address();
+        env.authorize_as_current_contract(vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token.clone(),
+                    fn_name: Symbol::new(&env, "transfer"),
+                    args: (contract.clone(), config.vault.clone(), amount).into_val(&env),
+                },
+                sub_invocations: vec![&env],
+            }),
+        ]);
+
+        let shares = VaultClient::new(&env, &config.vault).deposit(&contract, &amount);
+
+        position.shares = position.shares.checked_add(shares).ok_or(Error::Overflow)?;
+        position.principal = position.principal.checked_add(amount).ok_or(Error::Overflow)?;
+        env.storage().instance().set(&DataKey::VaultPosition(token.clone()), &position);
+        check_balances(&env, &token)?;
+
+        // Emit vault deposit event
+        env.events().publish(
+            (symbol_short!("vault_dep"),),
+            (token, amount, shares)
+        );
+
+        Ok(shares)
+    }
+
+    /// Redeem a token's vault position and split the yield
+    pub fn redeem_from_vault(env: Env, token: Address) -> Result<(), Error> {
+        let client: Address = env.storage().instance().get(&DataKey::Client)
+            .ok_or(Error::NotInitialized)?;
+        
+        client.require_auth();
+
+        if !env.storage().instance().has(&DataKey::VaultPosition(token.clone())) {
+            return Err(Error::VaultNotConfigured);
+        }
+
+        redeem_vault_position(&env, &token)
+    }
+
     /// Complete a milestone and release funds
     pub fn complete_milestone(env: Env, milestone_id: u32) -> Result<(), Error> {
         let client: Address = env.storage().instance().get(&DataKey::Client)
@@ -178,13 +484,15 @@ impl EscrowContract {
         if status == EscrowStatus::Disputed {
             return Err(Error::DisputeActive);
         }
+        if status != EscrowStatus::Active {
+            return Err(Error::EscrowNotActive);
+        }
 
         let mut milestones: Vec<Milestone> = env.storage().instance()
             .get(&DataKey::Milestones)
             .ok_or(Error::NotInitialized)?;
 
-        let mut found = false;
-        let mut release_amount = 0i128;
+        let mut released_milestone: Option<Milestone> = None;
 
         for i in 0..milestones.len() {
             if let Some(mut milestone) = milestones.get(i) {
@@ -193,28 +501,24 @@ impl EscrowContract {
                         return Err(Error::MilestoneAlreadyCompleted);
                     }
                     milestone.completed = true;
-                    release_amount = milestone.amount;
-                    milestones.set(i, milestone);
-                    found = true;
+                    milestones.set(i, milestone.clone());
+                    released_milestone = Some(milestone);
                     break;
                 }
             }
         }
 
-        if !found {
-            return Err(Error::MilestoneNotFound);
-        }
+        let milestone = released_milestone.ok_or(Error::MilestoneNotFound)?;
 
-        // Update released amount
-        let mut released: i128 = env.storage().instance().get(&DataKey::ReleasedAmount).unwrap();
-        released += release_amount;
-        env.storage().instance().set(&DataKey::ReleasedAmount, &released);
+        // Update released amount for the milestone's token
+        redeem_vault_position(&env, &milestone.token)?;
+        release(&env, &milestone.token, milestone.amount, PayoutSource::Milestone(milestone_id))?;
         env.storage().instance().set(&DataKey::Milestones, &milestones);
 
         // Emit completion event
         env.events().publish(
             (symbol_short!("complete"),),
-            (milestone_id, release_amount)
+            (milestone_id, milestone.token, milestone.amount)
         );
 
         Ok(())
@@ -222,6 +526,16 @@ impl EscrowContract {
 
     /// Release time-based funds
     pub fn release_time_based(env: Env, index: u32) -> Result<(), Error> {
+        let status: EscrowStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status == EscrowStatus::Disputed {
+            return Err(Error::DisputeActive);
+        }
+        if status != EscrowStatus::Active {
+            return Err(Error::EscrowNotActive);
+        }
+
         let mut schedule: Vec<TimeRelease> = env.storage().instance()
             .get(&DataKey::TimeSchedule)
             .ok_or(Error::NotInitialized)?;
@@ -241,72 +555,129 @@ impl EscrowContract {
         time_release.released = true;
         schedule.set(index, time_release.clone());
 
-        // Update released amount
-        let mut released: i128 = env.storage().instance().get(&DataKey::ReleasedAmount).unwrap();
-        released += time_release.amount;
-        env.storage().instance().set(&DataKey::ReleasedAmount, &released);
+        // Update released amount for the tranche's token
+        redeem_vault_position(&env, &time_release.token)?;
+        release(&env, &time_release.token, time_release.amount, PayoutSource::TimeRelease(index))?;
         env.storage().instance().set(&DataKey::TimeSchedule, &schedule);
 
         // Emit release event
         env.events().publish(
             (symbol_short!("release"),),
-            (index, time_release.amount)
+            (index, time_release.token, time_release.amount)
         );
 
         Ok(())
     }
 
-    /// Provider withdraws released funds
-    pub fn withdraw(env: Env) -> Result<i128, Error> {
+    /// Provider withdraws released funds of a given token
+    pub fn withdraw(env: Env, token: Address) -> Result<i128, Error> {
         let provider: Address = env.storage().instance().get(&DataKey::Provider)
             .ok_or(Error::NotInitialized)?;
         
         provider.require_auth();
 
-        let released: i128 = env.storage().instance().get(&DataKey::ReleasedAmount)
-            .ok_or(Error::NotInitialized)?;
+        let available = available(&env, &token)?;
 
-        if released <= 0 {
+        if available <= 0 {
             return Err(Error::InsufficientFunds);
         }
 
-        // Reset released amount after withdrawal
-        env.storage().instance().set(&DataKey::ReleasedAmount, &0i128);
+        let withdrawn = get_balance(&env, &DataKey::WithdrawnAmount(token.clone()))
+            .checked_add(available)
+            .ok_or(Error::Overflow)?;
+        env.storage().instance().set(&DataKey::WithdrawnAmount(token.clone()), &withdrawn);
+        check_balances(&env, &token)?;
+        record_payout(&env, PayoutKind::Withdrawn, PayoutSource::None, &token, available)?;
+
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &provider, &available);
 
         // Emit withdrawal event
         env.events().publish(
             (symbol_short!("withdraw"),),
-            (provider, released)
+            (provider, token, available)
         );
 
-        Ok(released)
+        Ok(available)
     }
 
     /// Initiate dispute
-    pub fn dispute(env: Env) -> Result<(), Error> {
+    pub fn dispute(env: Env, caller: Address) -> Result<(), Error> {
         let client: Address = env.storage().instance().get(&DataKey::Client)
             .ok_or(Error::NotInitialized)?;
         let provider: Address = env.storage().instance().get(&DataKey::Provider)
             .ok_or(Error::NotInitialized)?;
         
         // Either party can initiate dispute
-        if !client.require_auth_for_args(()).is_ok() && !provider.require_auth_for_args(()).is_ok() {
+        if caller != client && caller != provider {
             return Err(Error::Unauthorized);
         }
+        caller.require_auth();
+
+        let status: EscrowStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status != EscrowStatus::Active {
+            return Err(Error::EscrowNotActive);
+        }
 
         env.storage().instance().set(&DataKey::Status, &EscrowStatus::Disputed);
 
         // Emit dispute event
         env.events().publish(
             (symbol_short!("dispute"),),
-            ()
+            caller
+        );
+
+        Ok(())
+    }
+
+    /// Appoint the arbiter who resolves disputes (client and provider together)
+    pub fn set_arbiter(env: Env, arbiter: Address) -> Result<(), Error> {
+        let client: Address = env.storage().instance().get(&DataKey::Client)
+            .ok_or(Error::NotInitialized)?;
+        let provider: Address = env.storage().instance().get(&DataKey::Provider)
+            .ok_or(Error::NotInitialized)?;
+
+        client.require_auth();
+        provider.require_auth();
+
+        let status: EscrowStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+
+        if status == EscrowStatus::Disputed {
+            return Err(Error::DisputeActive);
+        }
+
+        env.storage().instance().set(&DataKey::Arbiter, &arbiter);
+
+        // Emit arbiter event
+        env.events().publish(
+            (symbol_short!("arbiter"),),
+            arbiter
         );
 
         Ok(())
     }
 
-    /// Resolve dispute (admin only in production)
+    /// Resolve dispute (the arbiter, or client and provider together if none is set)
+    ///
+    /// Unreleased funds of every token are refunded to the client, or released
+    /// to the provider when the dispute is resolved in their favour.
     pub fn resolve_dispute(env: Env, refund_to_client: bool) -> Result<(), Error> {
+        let client: Address = env.storage().instance().get(&DataKey::Client)
+            .ok_or(Error::NotInitialized)?;
+        let provider: Address = env.storage().instance().get(&DataKey::Provider)
+            .ok_or(Error::NotInitialized)?;
+
+        let arbiter: Option<Address> = env.storage().instance().get(&DataKey::Arbiter);
+        match arbiter {
+            Some(arbiter) => arbiter.require_auth(),
+            None => {
+                client.require_auth();
+                provider.require_auth();
+            }
+        }
+
         let status: EscrowStatus = env.storage().instance().get(&DataKey::Status)
             .ok_or(Error::NotInitialized)?;
 
@@ -314,31 +685,167 @@ impl EscrowContract {
             return Err(Error::NoDisputeActive);
         }
 
+        let tokens: Vec<Address> = env.storage().instance().get(&DataKey::Tokens)
+            .ok_or(Error::NotInitialized)?;
+
+        for token in tokens.iter() {
+            redeem_vault_position(&env, &token)?;
+
+            let remaining = unreleased(&env, &token)?;
+            if remaining <= 0 {
+                continue;
+            }
+
+            if refund_to_client {
+                let refunded = get_balance(&env, &DataKey::RefundedAmount(token.clone()))
+                    .checked_add(remaining)
+                    .ok_or(Error::Overflow)?;
+                env.storage().instance().set(&DataKey::RefundedAmount(token.clone()), &refunded);
+                check_balances(&env, &token)?;
+                record_payout(&env, PayoutKind::Refunded, PayoutSource::Dispute, &token, remaining)?;
+                token::Client::new(&env, &token).transfer(&env.current_contract_address(), &client, &remaining);
+            } else {
+                release(&env, &token, remaining, PayoutSource::Dispute)?;
+            }
+        }
+
         if refund_to_client {
             env.storage().instance().set(&DataKey::Status, &EscrowStatus::Cancelled);
         } else {
             env.storage().instance().set(&DataKey::Status, &EscrowStatus::Completed);
         }
 
+        // Emit resolution event
+        env.events().publish(
+            (symbol_short!("resolved"),),
+            refund_to_client
+        );
+
         Ok(())
     }
 
+    /// Get the full escrow state in a single call
+    pub fn get_escrow(env: Env) -> Result<EscrowInfo, Error> {
+        let client: Address = env.storage().instance().get(&DataKey::Client)
+            .ok_or(Error::NotInitialized)?;
+        let provider: Address = env.storage().instance().get(&DataKey::Provider)
+            .ok_or(Error::NotInitialized)?;
+        let status: EscrowStatus = env.storage().instance().get(&DataKey::Status)
+            .ok_or(Error::NotInitialized)?;
+        let release_type: bool = env.storage().instance().get(&DataKey::ReleaseType)
+            .ok_or(Error::NotInitialized)?;
+        let created_at: u64 = env.storage().instance().get(&DataKey::CreatedAt)
+            .ok_or(Error::NotInitialized)?;
+        let tokens: Vec<Address> = env.storage().instance().get(&DataKey::Tokens)
+            .ok_or(Error::NotInitialized)?;
+        let milestones: Vec<Milestone> = env.storage().instance().get(&DataKey::Milestones)
+            .ok_or(Error::NotInitialized)?;
+        let time_schedule: Vec<TimeRelease> = env.storage().instance().get(&DataKey::TimeSchedule)
+            .ok_or(Error::NotInitialized)?;
+
+        let mut balances: Vec<TokenBalance> = Vec::new(&env);
+        for token in tokens.iter() {
+            balances.push_back(TokenBalance {
+                token: token.clone(),
+                deposited: get_balance(&env, &DataKey::TotalAmount(token.clone())),
+                released: get_balance(&env, &DataKey::ReleasedAmount(token.clone())),
+                withdrawn: get_balance(&env, &DataKey::WithdrawnAmount(token.clone())),
+                refunded: get_balance(&env, &DataKey::RefundedAmount(token.clone())),
+                yielded: get_balance(&env, &DataKey::YieldAmount(token.clone())),
+                lost: get_balance(&env, &DataKey::LostAmount(token.clone())),
+                available: available(&env, &token)?,
+                remaining: unreleased(&env, &token)?,
+            });
+        }
+
+        let next_action = next_action(&status, &balances, &milestones, &time_schedule);
+
+        Ok(EscrowInfo {
+            client,
+            provider,
+            status,
+            release_type,
+            created_at,
+            balances,
+            milestones,
+            time_schedule,
+            next_action,
+        })
+    }
+
     /// Query functions
     pub fn get_status(env: Env) -> Result<EscrowStatus, Error> {
         env.storage().instance().get(&DataKey::Status)
             .ok_or(Error::NotInitialized)
     }
 
-    pub fn get_total_amount(env: Env) -> Result<i128, Error> {
-        env.storage().instance().get(&DataKey::TotalAmount)
+    pub fn get_tokens(env: Env) -> Result<Vec<Address>, Error> {
+        env.storage().instance().get(&DataKey::Tokens)
             .ok_or(Error::NotInitialized)
     }
 
-    pub fn get_released_amount(env: Env) -> Result<i128, Error> {
-        env.storage().instance().get(&DataKey::ReleasedAmount)
+    pub fn get_total_amount(env: Env, token: Address) -> i128 {
+        get_balance(&env, &DataKey::TotalAmount(token))
+    }
+
+    pub fn get_released_amount(env: Env, token: Address) -> i128 {
+        get_balance(&env, &DataKey::ReleasedAmount(token))
+    }
+
+    pub fn get_withdrawn_amount(env: Env, token: Address) -> i128 {
+        get_balance(&env, &DataKey::WithdrawnAmount(token))
+    }
+
+    pub fn get_refunded_amount(env: Env, token: Address) -> i128 {
+        get_balance(&env, &DataKey::RefundedAmount(token))
+    }
+
+    pub fn get_yield_amount(env: Env, token: Address) -> i128 {
+        get_balance(&env, &DataKey::YieldAmount(token))
+    }
+
+    pub fn get_lost_amount(env: Env, token: Address) -> i128 {
+        get_balance(&env, &DataKey::LostAmount(token))
+    }
+
+    /// Released funds the provider can still withdraw
+    pub fn get_available_amount(env: Env, token: Address) -> Result<i128, Error> {
+        available(&env, &token)
+    }
+
+    pub fn get_payout_count(env: Env) -> Result<u32, Error> {
+        env.storage().instance().get(&DataKey::PayoutCount)
             .ok_or(Error::NotInitialized)
     }
 
+    /// Page through the payout ledger, oldest first
+    pub fn get_payouts(env: Env, start: u32, limit: u32) -> Result<Vec<PayoutRecord>, Error> {
+        let count: u32 = env.storage().instance().get(&DataKey::PayoutCount)
+            .ok_or(Error::NotInitialized)?;
+
+        let mut payouts: Vec<PayoutRecord> = Vec::new(&env);
+        let end = start.saturating_add(limit).min(count);
+        for index in start..end {
+            let payout: PayoutRecord = env.storage().persistent().get(&DataKey::Payout(index))
+                .ok_or(Error::NotInitialized)?;
+            payouts.push_back(payout);
+        }
+
+        Ok(payouts)
+    }
+
+    pub fn get_arbiter(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Arbiter)
+    }
+
+    pub fn get_vault(env: Env, token: Address) -> Option<VaultConfig> {
+        env.storage().instance().get(&DataKey::Vault(token))
+    }
+
+    pub fn get_vault_position(env: Env, token: Address) -> Option<VaultPosition> {
+        env.storage().instance().get(&DataKey::VaultPosition(token))
+    }
+
     pub fn get_milestones(env: Env) -> Result<Vec<Milestone>, Error> {
         env.storage().instance().get(&DataKey::Milestones)
             .ok_or(Error::NotInitialized)
@@ -350,3 +857,233 @@ impl EscrowContract {
     }
 }
 
+// Per-token balances default to zero until the token is first used
+fn get_balance(env: &Env, key: &DataKey) -> i128 {
+    env.storage().instance().get(key).unwrap_or(0)
+}
+
+// Add a token to the escrow's token list, up to `MAX_TOKENS`
+fn register_token(env: &Env, token: &Address) -> Result<(), Error> {
+    let mut tokens: Vec<Address> = env.storage().instance()
+        .get(&DataKey::Tokens)
+        .ok_or(Error::NotInitialized)?;
+
+    if tokens.contains(token) {
+        return Ok(());
+    }
+
+    if tokens.len() >= MAX_TOKENS {
+        return Err(Error::TooManyTokens);
+    }
+
+    tokens.push_back(token.clone());
+    env.storage().instance().set(&DataKey::Tokens, &tokens);
+
+    // Emit token registration event
+    env.events().publish(
+        (symbol_short!("token"),),
+        token.clone()
+    );
+
+    Ok(())
+}
+
+// Deposited funds and provider yield of a token neither released, refunded nor
+// lost in the vault
+fn unreleased(env: &Env, token: &Address) -> Result<i128, Error> {
+    get_balance(env, &DataKey::TotalAmount(token.clone()))
+        .checked_add(get_balance(env, &DataKey::YieldAmount(token.clone())))
+        .and_then(|funded| funded.checked_sub(get_balance(env, &DataKey::ReleasedAmount(token.clone()))))
+        .and_then(|rest| rest.checked_sub(get_balance(env, &DataKey::RefundedAmount(token.clone()))))
+        .and_then(|rest| rest.checked_sub(get_balance(env, &DataKey::LostAmount(token.clone()))))
+        .ok_or(Error::Overflow)
+}
+
+// Released funds of a token not yet withdrawn by the provider
+fn available(env: &Env, token: &Address) -> Result<i128, Error> {
+    get_balance(env, &DataKey::ReleasedAmount(token.clone()))
+        .checked_sub(get_balance(env, &DataKey::WithdrawnAmount(token.clone())))
+        .ok_or(Error::Overflow)
+}
+
+// Released, refunded and lost funds may never exceed the deposit plus provider
+// yield, withdrawals may never exceed releases, and funds parked in a vault must
+// come out of the unreleased balance
+fn check_balances(env: &Env, token: &Address) -> Result<(), Error> {
+    let deposited = get_balance(env, &DataKey::TotalAmount(token.clone()));
+    let yielded = get_balance(env, &DataKey::YieldAmount(token.clone()));
+    let released = get_balance(env, &DataKey::ReleasedAmount(token.clone()));
+    let withdrawn = get_balance(env, &DataKey::WithdrawnAmount(token.clone()));
+    let refunded = get_balance(env, &DataKey::RefundedAmount(token.clone()));
+    let lost = get_balance(env, &DataKey::LostAmount(token.clone()));
+
+    if deposited < 0 || yielded < 0 || released < 0 || withdrawn < 0 || refunded < 0 || lost < 0 {
+        return Err(Error::BalanceInvariant);
+    }
+
+    let funded = deposited.checked_add(yielded).ok_or(Error::Overflow)?;
+
+    let paid_out = released
+        .checked_add(refunded)
+        .and_then(|sum| sum.checked_add(lost))
+        .ok_or(Error::Overflow)?;
+    if paid_out > funded || withdrawn > released {
+        return Err(Error::BalanceInvariant);
+    }
+
+    let position: Option<VaultPosition> = env.storage().instance().get(&DataKey::VaultPosition(token.clone()));
+    if let Some(position) = position {
+        if position.principal < 0 || position.principal > funded - paid_out {
+            return Err(Error::BalanceInvariant);
+        }
+    }
+
+    Ok(())
+}
+
+// Append an entry to the payout ledger
+fn record_payout(
+    env: &Env,
+    kind: PayoutKind,
+    source: PayoutSource,
+    token: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    let index: u32 = env.storage().instance().get(&DataKey::PayoutCount)
+        .ok_or(Error::NotInitialized)?;
+
+    let key = DataKey::Payout(index);
+    env.storage().persistent().set(&key, &PayoutRecord {
+        kind,
+        source,
+        token: token.clone(),
+        amount,
+        timestamp: env.ledger().timestamp(),
+    });
+    env.storage().persistent().extend_ttl(&key, PAYOUT_TTL_THRESHOLD, PAYOUT_TTL_EXTEND_TO);
+
+    let count = index.checked_add(1).ok_or(Error::Overflow)?;
+    env.storage().instance().set(&DataKey::PayoutCount, &count);
+
+    Ok(())
+}
+
+// Disputes block everything else; withdrawable funds come before pending
+// milestones, which come before the earliest unreleased time tranche
+fn next_action(
+    status: &EscrowStatus,
+    balances: &Vec<TokenBalance>,
+    milestones: &Vec<Milestone>,
+    time_schedule: &Vec<TimeRelease>,
+) -> NextAction {
+    if *status == EscrowStatus::Disputed {
+        return NextAction::ResolveDispute;
+    }
+
+    if let Some(balance) = balances.iter().find(|balance| balance.available > 0) {
+        return NextAction::Withdraw(balance.token);
+    }
+
+    if *status != EscrowStatus::Active {
+        return NextAction::None;
+    }
+
+    if let Some(milestone) = milestones.iter().find(|milestone| !milestone.completed) {
+        return NextAction::CompleteMilestone(milestone.id);
+    }
+
+    let mut next: Option<(u32, u64)> = None;
+    for (index, time_release) in time_schedule.iter().enumerate() {
+        if time_release.released {
+            continue;
+        }
+        if next.is_none_or(|(_, release_time)| time_release.release_time < release_time) {
+            next = Some((index as u32, time_release.release_time));
+        }
+    }
+
+    match next {
+        Some((index, release_time)) => NextAction::ReleaseTimeBased(index, release_time),
+        None => NextAction::None,
+    }
+}
+
+// Move deposited funds of a token into the provider's withdrawable balance
+fn release(env: &Env, token: &Address, amount: i128, source: PayoutSource) -> Result<(), Error> {
+    if amount > unreleased(env, token)? {
+        return Err(Error::InsufficientFunds);
+    }
+
+    let released = get_balance(env, &DataKey::ReleasedAmount(token.clone()))
+        .checked_add(amount)
+        .ok_or(Error::Overflow)?;
+    env.storage().instance().set(&DataKey::ReleasedAmount(token.clone()), &released);
+    check_balances(env, token)?;
+
+    record_payout(env, PayoutKind::Released, source, token, amount)
+}
+
+// Redeem the vault position of a token, if any. Yield is split between the
+// provider (released immediately) and the client (paid out); a loss is
+// recorded separately and comes out of the unreleased balance.
+fn redeem_vault_position(env: &Env, token: &Address) -> Result<(), Error> {
+    let position: VaultPosition = match env.storage().instance().get(&DataKey::VaultPosition(token.clone())) {
+        Some(position) => position,
+        None => return Ok(()),
+    };
+    let config: VaultConfig = env.storage().instance().get(&DataKey::Vault(token.clone()))
+        .ok_or(Error::VaultNotConfigured)?;
+    let client: Address = env.storage().instance().get(&DataKey::Client)
+        .ok_or(Error::NotInitialized)?;
+
+    env.storage().instance().remove(&DataKey::VaultPosition(token.clone()));
+
+    let contract = env.current_contract_address();
+    let redeemed = VaultClient::new(env, &config.vault).redeem(&contract, &position.shares);
+    let earned = redeemed.checked_sub(position.principal).ok_or(Error::Overflow)?;
+
+    let mut provider_yield = 0i128;
+    let mut client_yield = 0i128;
+
+    if earned > 0 {
+        provider_yield = earned
+            .checked_mul(config.provider_share_bps as i128)
+            .ok_or(Error::Overflow)?
+            / BPS_DENOMINATOR;
+        client_yield = earned - provider_yield;
+
+        // Provider's yield is tracked apart from deposits and released straight away
+        let yielded = get_balance(env, &DataKey::YieldAmount(token.clone()))
+            .checked_add(provider_yield)
+            .ok_or(Error::Overflow)?;
+        env.storage().instance().set(&DataKey::YieldAmount(token.clone()), &yielded);
+        if provider_yield > 0 {
+            release(env, token, provider_yield, PayoutSource::VaultYield)?;
+        }
+
+        if client_yield > 0 {
+            record_payout(env, PayoutKind::YieldPaid, PayoutSource::VaultYield, token, client_yield)?;
+            token::Client::new(env, token).transfer(&contract, &client, &client_yield);
+        }
+    } else if earned < 0 {
+        let loss = earned.checked_neg().ok_or(Error::Overflow)?;
+        let lost = get_balance(env, &DataKey::LostAmount(token.clone()))
+            .checked_add(loss)
+            .ok_or(Error::Overflow)?;
+        env.storage().instance().set(&DataKey::LostAmount(token.clone()), &lost);
+        check_balances(env, token)?;
+
+        record_payout(env, PayoutKind::VaultLoss, PayoutSource::VaultYield, token, loss)?;
+    }
+
+    // Emit yield event
+    env.events().publish(
+        (symbol_short!("yield"),),
+        (token.clone(), provider_yield, client_yield)
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,126 @@
+extern crate std;
+
+use super::*;
+use mock_vault_contract::{MockVault, MockVaultClient};
+use soroban_sdk::{
+    testutils::{Address as _, IssuerFlags},
+    token::StellarAssetClient,
+    Env,
+};
+
+struct Setup<'a> {
+    env: Env,
+    escrow: EscrowContractClient<'a>,
+    escrow_id: Address,
+    vault: MockVaultClient<'a>,
+    vault_id: Address,
+    token: Address,
+    client: Address,
+    provider: Address,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let issuer = Address::generate(&env);
+    let sac = env.register_stellar_asset_contract_v2(issuer);
+    sac.issuer().set_flag(IssuerFlags::RevocableFlag);
+    sac.issuer().set_flag(IssuerFlags::ClawbackEnabledFlag);
+    let token = sac.address();
+
+    let client = Address::generate(&env);
+    let provider = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&client, &1000);
+
+    let escrow_id = env.register_contract(None, EscrowContract);
+    let escrow = EscrowContractClient::new(&env, &escrow_id);
+    escrow.initialize(&client, &provider, &true);
+    escrow.add_token(&token);
+    escrow.deposit(&client, &token, &1000);
+
+    let vault_id = env.register_contract(None, MockVault);
+    let vault = MockVaultClient::new(&env, &vault_id);
+    vault.initialize(&token);
+
+    Setup { env, escrow, escrow_id, vault, vault_id, token, client, provider }
+}
+
+#[test]
+fn test_vault_yield_is_split_on_release() {
+    let s = setup();
+    let balance = |id: &Address| token::Client::new(&s.env, &s.token).balance(id);
+
+    s.escrow.add_milestone(&1, &s.token, &1000);
+    s.escrow.set_vault(&s.token, &s.vault_id, &5000);
+
+    assert_eq!(s.escrow.deposit_to_vault(&s.token, &800), 800);
+    assert_eq!(s.escrow.try_deposit_to_vault(&s.token, &300), Err(Ok(Error::InsufficientFunds)));
+    assert_eq!(balance(&s.vault_id), 800);
+
+    // 100 of yield, split evenly between provider and client
+    let whale = Address::generate(&s.env);
+    StellarAssetClient::new(&s.env, &s.token).mint(&whale, &100);
+    s.vault.accrue(&whale, &100);
+
+    s.escrow.complete_milestone(&1);
+    assert_eq!(s.escrow.get_released_amount(&s.token), 1050);
+    assert_eq!(s.escrow.get_total_amount(&s.token), 1000);
+    assert_eq!(s.escrow.get_yield_amount(&s.token), 50);
+
+    let summary = s.escrow.get_escrow().balances.get(0).unwrap();
+    assert_eq!((summary.deposited, summary.yielded, summary.remaining), (1000, 50, 0));
+
+    assert_eq!(s.escrow.withdraw(&s.token), 1050);
+
+    assert_eq!(balance(&s.provider), 1050);
+    assert_eq!(balance(&s.client), 50);
+    assert_eq!(balance(&s.escrow_id), 0);
+    assert_eq!(balance(&s.vault_id), 0);
+
+    let payouts = s.escrow.get_payouts(&0, &10);
+    assert!(payouts.iter().any(|p| p.kind == PayoutKind::YieldPaid && p.amount == 50));
+}
+
+#[test]
+fn test_vault_loss_is_recorded() {
+    let s = setup();
+    let balance = |id: &Address| token::Client::new(&s.env, &s.token).balance(id);
+
+    s.escrow.add_milestone(&1, &s.token, &700);
+    s.escrow.set_vault(&s.token, &s.vault_id, &5000);
+    s.escrow.deposit_to_vault(&s.token, &800);
+
+    // The vault loses 200 of the principal
+    StellarAssetClient::new(&s.env, &s.token).clawback(&s.vault_id, &200);
+
+    s.escrow.complete_milestone(&1);
+    assert_eq!(s.escrow.get_total_amount(&s.token), 1000);
+    assert_eq!(s.escrow.get_lost_amount(&s.token), 200);
+
+    let payouts = s.escrow.get_payouts(&0, &10);
+    assert!(payouts.iter().any(|p| p.kind == PayoutKind::VaultLoss && p.amount == 200));
+
+    let summary = s.escrow.get_escrow().balances.get(0).unwrap();
+    assert_eq!((summary.deposited, summary.lost, summary.remaining), (1000, 200, 100));
+
+    assert_eq!(s.escrow.withdraw(&s.token), 700);
+    s.escrow.dispute(&s.provider);
+    s.escrow.resolve_dispute(&true);
+    assert_eq!(balance(&s.client), 100);
+    assert_eq!(balance(&s.escrow_id), 0);
+}
+
+#[test]
+fn test_vault_must_match_token() {
+    let s = setup();
+
+    let other = s.env.register_stellar_asset_contract_v2(Address::generate(&s.env)).address();
+    let other_vault = s.env.register_contract(None, MockVault);
+    MockVaultClient::new(&s.env, &other_vault).initialize(&other);
+
+    assert_eq!(
+        s.escrow.try_set_vault(&s.token, &other_vault, &5000),
+        Err(Ok(Error::VaultTokenMismatch))
+    );
+}
@@ -0,0 +1,323 @@
+#![no_std]
+
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracterror, contracttype, Address, BytesN, Env, symbol_short,
+};
+
+// Contract errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    InvalidConfig = 3,
+    PoolNotFunded = 4,
+    NotBacker = 5,
+    ProposalNotFound = 6,
+    VotingClosed = 7,
+    VotingNotEnded = 8,
+    AlreadyVoted = 9,
+    Overflow = 10,
+}
+
+// Mirror of the crowdfunding pool's status, decoded from `get_status`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PoolStatus {
+    Funding,
+    Funded,
+    Failed,
+    Abandoned,
+    Cancelled,
+}
+
+// Governance parameters
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GovernanceConfig {
+    pub pool: Address,
+    pub voting_period: u64,
+    pub quorum_bps: u32, // Share of the total raised that must vote, in bps
+}
+
+// Ballot choice
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VoteChoice {
+    For,
+    Against,
+    Abstain, // Counts towards quorum only
+}
+
+// Recorded outcome of a proposal
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProposalStatus {
+    Active,
+    Passed,
+    Rejected,
+    NoQuorum,
+}
+
+// Proposal put to the pool's backers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub id: u32,
+    pub proposer: Address,
+    pub description_hash: BytesN<32>,
+    pub created_at: u64,
+    pub voting_ends: u64,
+    pub votes_for: i128,     // Contribution-weighted
+    pub votes_against: i128, // Contribution-weighted
+    pub votes_abstain: i128, // Contribution-weighted
+    pub status: ProposalStatus,
+}
+
+// Storage keys
+#[contracttype]
+pub enum DataKey {
+    Config,
+    ProposalCount,
+    Proposal(u32),      // Persistent
+    Vote(u32, Address), // Persistent
+}
+
+const PROPOSAL_TTL_THRESHOLD: u32 = 518_400;
+const PROPOSAL_TTL_EXTEND_TO: u32 = 3_110_400;
+
+// Basis-point denominator for the quorum
+const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Pool getters read for voting weights.
+#[contractclient(name = "PoolClient")]
+pub trait PoolInterface {
+    fn get_status(env: Env) -> PoolStatus;
+    fn get_project_owner(env: Env) -> Address;
+    fn get_total_raised(env: Env) -> i128;
+    fn get_contribution(env: Env, contributor: Address) -> i128;
+}
+
+/// Backer governance for a funded crowdfunding pool.
+///
+/// Backers and the project owner raise proposals identified by a hash of their
+/// off-chain description; backers vote weighted by their recorded contribution.
+/// Outcomes are recorded on-chain for other contracts to query.
+#[contract]
+pub struct GovernanceContract;
+
+#[contractimpl]
+impl GovernanceContract {
+    /// Initialize governance for a pool
+    pub fn initialize(env: Env, pool: Address, voting_period: u64, quorum_bps: u32) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Config) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        if voting_period == 0 || quorum_bps as i128 > BPS_DENOMINATOR {
+            return Err(Error::InvalidConfig);
+        }
+
+        // Only the pool's owner can attach governance to it
+        PoolClient::new(&env, &pool).get_project_owner().require_auth();
+
+        env.storage().instance().set(&DataKey::Config, &GovernanceConfig {
+            pool: pool.clone(),
+            voting_period,
+            quorum_bps,
+        });
+        env.storage().instance().set(&DataKey::ProposalCount, &0u32);
+
+        // Emit initialization event
+        env.events().publish(
+            (symbol_short!("init"),),
+            (pool, voting_period, quorum_bps)
+        );
+
+        Ok(())
+    }
+
+    /// Raise a proposal (a backer or the project owner, once the pool is funded)
+    pub fn propose(env: Env, proposer: Address, description_hash: BytesN<32>) -> Result<u32, Error> {
+        proposer.require_auth();
+
+        let config: GovernanceConfig = env.storage().instance().get(&DataKey::Config)
+            .ok_or(Error::NotInitialized)?;
+        let pool = PoolClient::new(&env, &config.pool);
+
+        if pool.get_status() != PoolStatus::Funded {
+            return Err(Error::PoolNotFunded);
+        }
+
+        if pool.get_contribution(&proposer) <= 0 && pool.get_project_owner() != proposer {
+            return Err(Error::NotBacker);
+        }
+
+        let id: u32 = env.storage().instance().get(&DataKey::ProposalCount)
+            .ok_or(Error::NotInitialized)?;
+        let created_at = env.ledger().timestamp();
+        let voting_ends = created_at.checked_add(config.voting_period).ok_or(Error::Overflow)?;
+
+        let key = DataKey::Proposal(id);
+        env.storage().persistent().set(&key, &Proposal {
+            id,
+            proposer: proposer.clone(),
+            description_hash: description_hash.clone(),
+            created_at,
+            voting_ends,
+            votes_for: 0,
+            votes_against: 0,
+            votes_abstain: 0,
+            status: ProposalStatus::Active,
+        });
+        env.storage().persistent().extend_ttl(&key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL_EXTEND_TO);
+        env.storage().instance().set(&DataKey::ProposalCount, &id.checked_add(1).ok_or(Error::Overflow)?);
+
+        // Emit proposal event
+        env.events().publish(
+            (symbol_short!("propose"),),
+            (id, proposer, description_hash, voting_ends)
+        );
+
+        Ok(id)
+    }
+
+    /// Vote on an active proposal, weighted by the voter's contribution to the pool
+    pub fn vote(env: Env, voter: Address, proposal_id: u32, choice: VoteChoice) -> Result<i128, Error> {
+        voter.require_auth();
+
+        let config: GovernanceConfig = env.storage().instance().get(&DataKey::Config)
+            .ok_or(Error::NotInitialized)?;
+
+        let key = DataKey::Proposal(proposal_id);
+        let mut proposal: Proposal = env.storage().persistent().get(&key)
+            .ok_or(Error::ProposalNotFound)?;
+
+        if proposal.status != ProposalStatus::Active || env.ledger().timestamp() >= proposal.voting_ends {
+            return Err(Error::VotingClosed);
+        }
+
+        let pool = PoolClient::new(&env, &config.pool);
+        if pool.get_status() != PoolStatus::Funded {
+            return Err(Error::PoolNotFunded);
+        }
+
+        let weight = pool.get_contribution(&voter);
+        if weight <= 0 {
+            return Err(Error::NotBacker);
+        }
+
+        let vote_key = DataKey::Vote(proposal_id, voter.clone());
+        if env.storage().persistent().has(&vote_key) {
+            return Err(Error::AlreadyVoted);
+        }
+
+        match choice {
+            VoteChoice::For => {
+                proposal.votes_for = proposal.votes_for.checked_add(weight).ok_or(Error::Overflow)?;
+            }
+            VoteChoice::Against => {
+                proposal.votes_against = proposal.votes_against.checked_add(weight).ok_or(Error::Overflow)?;
+            }
+            VoteChoice::Abstain => {
+                proposal.votes_abstain = proposal.votes_abstain.checked_add(weight).ok_or(Error::Overflow)?;
+            }
+        }
+
+        env.storage().persistent().set(&key, &proposal);
+        env.storage().persistent().extend_ttl(&key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL_EXTEND_TO);
+        env.storage().persistent().set(&vote_key, &choice);
+        env.storage().persistent().extend_ttl(&vote_key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL_EXTEND_TO);
+
+        // Emit vote event
+        env.events().publish(
+            (symbol_short!("vote"),),
+            (proposal_id, voter, choice, weight)
+        );
+
+        Ok(weight)
+    }
+
+    /// Record a proposal's outcome once voting has ended (anyone). It passes if
+    /// turnout meets the quorum and votes in favour outweigh those against.
+    pub fn finalize(env: Env, proposal_id: u32) -> Result<ProposalStatus, Error> {
+        let config: GovernanceConfig = env.storage().instance().get(&DataKey::Config)
+            .ok_or(Error::NotInitialized)?;
+
+        let key = DataKey::Proposal(proposal_id);
+        let mut proposal: Proposal = env.storage().persistent().get(&key)
+            .ok_or(Error::ProposalNotFound)?;
+
+        if proposal.status != ProposalStatus::Active {
+            return Err(Error::VotingClosed);
+        }
+
+        if env.ledger().timestamp() < proposal.voting_ends {
+            return Err(Error::VotingNotEnded);
+        }
+
+        let total_raised = PoolClient::new(&env, &config.pool).get_total_raised();
+        let turnout = proposal.votes_for
+            .checked_add(proposal.votes_against)
+            .and_then(|votes| votes.checked_add(proposal.votes_abstain))
+            .ok_or(Error::Overflow)?;
+        let quorum_met = turnout.checked_mul(BPS_DENOMINATOR).ok_or(Error::Overflow)?
+            >= total_raised.checked_mul(config.quorum_bps as i128).ok_or(Error::Overflow)?;
+
+        proposal.status = if !quorum_met {
+            ProposalStatus::NoQuorum
+        } else if proposal.votes_for > proposal.votes_against {
+            ProposalStatus::Passed
+        } else {
+            ProposalStatus::Rejected
+        };
+
+        env.storage().persistent().set(&key, &proposal);
+        env.storage().persistent().extend_ttl(&key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL_EXTEND_TO);
+
+        // Emit outcome event
+        env.events().publish(
+            (symbol_short!("outcome"),),
+            (proposal_id, proposal.status.clone(), turnout)
+        );
+
+        Ok(proposal.status)
+    }
+
+    /// Get a proposal
+    pub fn get_proposal(env: Env, proposal_id: u32) -> Result<Proposal, Error> {
+        env.storage().persistent().get(&DataKey::Proposal(proposal_id))
+            .ok_or(Error::ProposalNotFound)
+    }
+
+    /// Get a proposal's recorded outcome, `Active` until finalized
+    pub fn get_outcome(env: Env, proposal_id: u32) -> Result<ProposalStatus, Error> {
+        let proposal: Proposal = env.storage().persistent().get(&DataKey::Proposal(proposal_id))
+            .ok_or(Error::ProposalNotFound)?;
+        Ok(proposal.status)
+    }
+
+    /// Check whether a proposal has passed
+    pub fn has_passed(env: Env, proposal_id: u32) -> bool {
+        let proposal: Option<Proposal> = env.storage().persistent().get(&DataKey::Proposal(proposal_id));
+        proposal.is_some_and(|proposal| proposal.status == ProposalStatus::Passed)
+    }
+
+    /// Get a voter's ballot on a proposal, if any
+    pub fn get_vote(env: Env, proposal_id: u32, voter: Address) -> Option<VoteChoice> {
+        env.storage().persistent().get(&DataKey::Vote(proposal_id, voter))
+    }
+
+    /// Get number of proposals raised
+    pub fn get_proposal_count(env: Env) -> Result<u32, Error> {
+        env.storage().instance().get(&DataKey::ProposalCount)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get governance parameters
+    pub fn get_config(env: Env) -> Result<GovernanceConfig, Error> {
+        env.storage().instance().get(&DataKey::Config)
+            .ok_or(Error::NotInitialized)
+    }
+}
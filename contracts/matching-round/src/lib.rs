@@ -0,0 +1,557 @@
+#![no_std]
+
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracterror, contracttype, token, Address, Env, Vec, symbol_short,
+};
+
+// Contract errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    InvalidAmount = 3,
+    InvalidDeadline = 4,
+    RoundEnded = 5,
+    RoundNotEnded = 6,
+    ProjectNotFound = 7,
+    ProjectAlreadyAdded = 8,
+    TooManyProjects = 9,
+    TokenMismatch = 10,
+    PoolNotSettled = 11,
+    AlreadyTallied = 12,
+    NotTallied = 13,
+    AlreadyFinalized = 14,
+    Overflow = 15,
+    NotFinalized = 16,
+    NothingToClaim = 17,
+}
+
+// Mirror of the crowdfunding pool's status, decoded from `get_status`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PoolStatus {
+    Funding,
+    Funded,
+    Failed,
+    Abandoned,
+    Cancelled,
+}
+
+// Mirror of the pool's tranche approval rule, decoded as part of its plan
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TrancheApproval {
+    Vote,
+    NoObjection(u32),
+}
+
+// Mirror of the pool's disbursement plan, decoded from `get_disbursement_plan`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisbursementPlan {
+    pub tranches: Vec<u32>, // Share per tranche, in bps summing to 10,000
+    pub approval: TrancheApproval,
+    pub voting_period: u64,
+    pub released: u32,      // Number of tranches paid out
+    pub disbursed: i128,
+}
+
+// Quadratic tally of a project's pool
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectTally {
+    pub pool: Address,
+    pub cursor: u32,        // Next contributor index to tally
+    pub contributors: u32,  // Eligible contributors counted
+    pub sqrt_sum: i128,     // Sum of square roots of eligible contributions
+    pub tallied: bool,
+    pub matched: i128,      // Match allotted at finalization
+    pub paid: i128,         // Match paid to the project owner
+    pub returned: i128,     // Match returned to the admin after the project was abandoned
+}
+
+// Storage keys
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Token,
+    RoundEnd,
+    SybilHook,
+    Projects, // Pools in the round, in order added
+    Tally(Address),
+    MatchingFund,
+    Finalized,
+}
+
+// Projects are paid in a single finalize call, so their number is bounded
+const MAX_PROJECTS: u32 = 50;
+
+// Basis-point denominator for disbursement tranches
+const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Pool getters read when tallying a project.
+#[contractclient(name = "PoolClient")]
+pub trait PoolInterface {
+    fn get_token(env: Env) -> Address;
+    fn get_status(env: Env) -> PoolStatus;
+    fn get_project_owner(env: Env) -> Address;
+    fn get_contributor_count(env: Env) -> u32;
+    fn get_contributors(env: Env, start: u32, limit: u32) -> Vec<Address>;
+    fn get_contribution(env: Env, contributor: Address) -> i128;
+    fn get_disbursement_plan(env: Env) -> Option<DisbursementPlan>;
+}
+
+/// Sybil-resistance hook consulted for every contributor, e.g. an allowlist
+/// or proof-of-personhood registry. Ineligible contributors count for nothing.
+#[contractclient(name = "SybilHookClient")]
+pub trait SybilHookInterface {
+    fn is_eligible(env: Env, contributor: Address) -> bool;
+}
+
+/// Quadratic-funding matching round over crowdfunding pools.
+///
+/// After the round ends each project's pool is tallied in pages, scoring it by
+/// the square of the sum of square roots of its eligible contributions. The
+/// matching fund is then split between projects in proportion to their scores.
+/// A project's match is paid to its owner straight away, unless the pool has a
+/// disbursement plan, in which case it is held and paid out as backers approve
+/// tranches (and returned to the admin if the project is abandoned).
+#[contract]
+pub struct MatchingRound;
+
+#[contractimpl]
+impl MatchingRound {
+    /// Initialize the round
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        token: Address,
+        round_end: u64,
+        sybil_hook: Option<Address>,
+    ) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        if round_end <= env.ledger().timestamp() {
+            return Err(Error::InvalidDeadline);
+        }
+
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::RoundEnd, &round_end);
+        env.storage().instance().set(&DataKey::Projects, &Vec::<Address>::new(&env));
+        env.storage().instance().set(&DataKey::MatchingFund, &0i128);
+        if let Some(hook) = &sybil_hook {
+            env.storage().instance().set(&DataKey::SybilHook, hook);
+        }
+
+        // Emit initialization event
+        env.events().publish(
+            (symbol_short!("init"),),
+            (admin, token, round_end, sybil_hook)
+        );
+
+        Ok(())
+    }
+
+    /// Add a crowdfunding pool to the round (admin only, before the round ends)
+    pub fn add_project(env: Env, pool: Address) -> Result<(), Error> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+
+        admin.require_auth();
+
+        let round_end: u64 = env.storage().instance().get(&DataKey::RoundEnd)
+            .ok_or(Error::NotInitialized)?;
+
+        if env.ledger().timestamp() >= round_end {
+            return Err(Error::RoundEnded);
+        }
+
+        let mut projects: Vec<Address> = env.storage().instance().get(&DataKey::Projects)
+            .ok_or(Error::NotInitialized)?;
+
+        if projects.contains(&pool) {
+            return Err(Error::ProjectAlreadyAdded);
+        }
+
+        if projects.len() >= MAX_PROJECTS {
+            return Err(Error::TooManyProjects);
+        }
+
+        // Matches are paid in the pool's own token
+        let token: Address = env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        if PoolClient::new(&env, &pool).get_token() != token {
+            return Err(Error::TokenMismatch);
+        }
+
+        projects.push_back(pool.clone());
+        env.storage().instance().set(&DataKey::Projects, &projects);
+        env.storage().instance().set(&DataKey::Tally(pool.clone()), &ProjectTally {
+            pool: pool.clone(),
+            cursor: 0,
+            contributors: 0,
+            sqrt_sum: 0,
+            tallied: false,
+            matched: 0,
+            paid: 0,
+            returned: 0,
+        });
+
+        // Emit project registration event
+        env.events().publish(
+            (symbol_short!("project"),),
+            pool
+        );
+
+        Ok(())
+    }
+
+    /// Remove a project from the round (admin only, until the round is finalized),
+    /// e.g. a pool that cannot settle in time and would otherwise block `finalize`
+    pub fn remove_project(env: Env, pool: Address) -> Result<(), Error> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+
+        admin.require_auth();
+
+        if env.storage().instance().has(&DataKey::Finalized) {
+            return Err(Error::AlreadyFinalized);
+        }
+
+        let mut projects: Vec<Address> = env.storage().instance().get(&DataKey::Projects)
+            .ok_or(Error::NotInitialized)?;
+
+        let index = projects.first_index_of(&pool).ok_or(Error::ProjectNotFound)?;
+        projects.remove(index);
+        env.storage().instance().set(&DataKey::Projects, &projects);
+        env.storage().instance().remove(&DataKey::Tally(pool.clone()));
+
+        // Emit project removal event
+        env.events().publish(
+            (symbol_short!("proj_rm"),),
+            pool
+        );
+
+        Ok(())
+    }
+
+    /// Add tokens to the matching fund (anyone, until the round is finalized)
+    pub fn fund(env: Env, from: Address, amount: i128) -> Result<(), Error> {
+        from.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        if env.storage().instance().has(&DataKey::Finalized) {
+            return Err(Error::AlreadyFinalized);
+        }
+
+        let token: Address = env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        let matching_fund: i128 = env.storage().instance().get(&DataKey::MatchingFund)
+            .ok_or(Error::NotInitialized)?;
+        let matching_fund = matching_fund.checked_add(amount).ok_or(Error::Overflow)?;
+
+        token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &amount);
+        env.storage().instance().set(&DataKey::MatchingFund, &matching_fund);
+
+        // Emit funding event
+        env.events().publish(
+            (symbol_short!("fund"),),
+            (from, amount, matching_fund)
+        );
+
+        Ok(())
+    }
+
+    /// Tally the next `limit` contributors of a project's pool (anyone, after the
+    /// round ends and the pool has settled). Returns true once the pool is fully
+    /// tallied. Pools that did not get funded score zero.
+    pub fn tally(env: Env, pool: Address, limit: u32) -> Result<bool, Error> {
+        let round_end: u64 = env.storage().instance().get(&DataKey::RoundEnd)
+            .ok_or(Error::NotInitialized)?;
+
+        if env.ledger().timestamp() < round_end {
+            return Err(Error::RoundNotEnded);
+        }
+
+        let mut tally: ProjectTally = env.storage().instance().get(&DataKey::Tally(pool.clone()))
+            .ok_or(Error::ProjectNotFound)?;
+
+        if tally.tallied {
+            return Err(Error::AlreadyTallied);
+        }
+
+        let pool_client = PoolClient::new(&env, &pool);
+        let status = pool_client.get_status();
+
+        if status == PoolStatus::Funding {
+            return Err(Error::PoolNotSettled);
+        }
+
+        let count = pool_client.get_contributor_count();
+        if status != PoolStatus::Funded {
+            tally.cursor = count;
+        }
+
+        let hook: Option<Address> = env.storage().instance().get(&DataKey::SybilHook);
+        for contributor in pool_client.get_contributors(&tally.cursor, &limit).iter() {
+            tally.cursor += 1;
+
+            let eligible = match &hook {
+                Some(hook) => SybilHookClient::new(&env, hook).is_eligible(&contributor),
+                None => true,
+            };
+            if !eligible {
+                continue;
+            }
+
+            let contribution = pool_client.get_contribution(&contributor);
+            if contribution <= 0 {
+                continue;
+            }
+
+            tally.sqrt_sum = tally.sqrt_sum.checked_add(isqrt(contribution)).ok_or(Error::Overflow)?;
+            tally.contributors += 1;
+        }
+
+        tally.tallied = tally.cursor >= count;
+        env.storage().instance().set(&DataKey::Tally(pool.clone()), &tally);
+
+        // Emit tally event
+        env.events().publish(
+            (symbol_short!("tally"),),
+            (pool, tally.cursor, tally.sqrt_sum, tally.tallied)
+        );
+
+        Ok(tally.tallied)
+    }
+
+    /// Split the matching fund between projects in proportion to their quadratic
+    /// scores (anyone, once the round has ended and every project is tallied).
+    /// Owners of pools without a disbursement plan are paid at once; other
+    /// matches are held for `claim_match`. If no project scored, the fund is
+    /// returned to the admin.
+    pub fn finalize(env: Env) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Finalized) {
+            return Err(Error::AlreadyFinalized);
+        }
+
+        let round_end: u64 = env.storage().instance().get(&DataKey::RoundEnd)
+            .ok_or(Error::NotInitialized)?;
+
+        if env.ledger().timestamp() < round_end {
+            return Err(Error::RoundNotEnded);
+        }
+
+        let projects: Vec<Address> = env.storage().instance().get(&DataKey::Projects)
+            .ok_or(Error::NotInitialized)?;
+
+        let mut tallies: Vec<ProjectTally> = Vec::new(&env);
+        let mut total_score: i128 = 0;
+        for pool in projects.iter() {
+            let tally: ProjectTally = env.storage().instance().get(&DataKey::Tally(pool))
+                .ok_or(Error::ProjectNotFound)?;
+            if !tally.tallied {
+                return Err(Error::NotTallied);
+            }
+            total_score = total_score.checked_add(score(&tally)?).ok_or(Error::Overflow)?;
+            tallies.push_back(tally);
+        }
+
+        let matching_fund: i128 = env.storage().instance().get(&DataKey::MatchingFund)
+            .ok_or(Error::NotInitialized)?;
+        let token: Address = env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        let token_client = token::Client::new(&env, &token);
+
+        env.storage().instance().set(&DataKey::Finalized, &true);
+
+        if total_score == 0 {
+            let admin: Address = env.storage().instance().get(&DataKey::Admin)
+                .ok_or(Error::NotInitialized)?;
+            if matching_fund > 0 {
+                token_client.transfer(&env.current_contract_address(), &admin, &matching_fund);
+            }
+
+            // Emit finalization event
+            env.events().publish(
+                (symbol_short!("finalize"),),
+                (0i128, matching_fund)
+            );
+
+            return Ok(());
+        }
+
+        // Rounding dust stays in the contract
+        let mut allotted: i128 = 0;
+        for mut tally in tallies.iter() {
+            let matched = matching_fund.checked_mul(score(&tally)?).ok_or(Error::Overflow)? / total_score;
+            if matched == 0 {
+                continue;
+            }
+
+            tally.matched = matched;
+            allotted = allotted.checked_add(matched).ok_or(Error::Overflow)?;
+
+            // Funds of a pool with a disbursement plan are only released by backer
+            // vote, so its match waits for the same approvals
+            let pool_client = PoolClient::new(&env, &tally.pool);
+            if pool_client.get_disbursement_plan().is_none() {
+                let project_owner = pool_client.get_project_owner();
+                tally.paid = matched;
+                token_client.transfer(&env.current_contract_address(), &project_owner, &matched);
+            }
+            env.storage().instance().set(&DataKey::Tally(tally.pool.clone()), &tally);
+
+            // Emit match event
+            env.events().publish(
+                (symbol_short!("match"),),
+                (tally.pool, matched, tally.paid)
+            );
+        }
+
+        // Emit finalization event
+        env.events().publish(
+            (symbol_short!("finalize"),),
+            (total_score, allotted)
+        );
+
+        Ok(())
+    }
+
+    /// Pay a held match in step with the pool's released tranches (anyone, after
+    /// finalization). Once the project is abandoned, the part of the match for
+    /// tranches never released is returned to the admin.
+    /// Returns the amount paid to the project owner.
+    pub fn claim_match(env: Env, pool: Address) -> Result<i128, Error> {
+        if !env.storage().instance().has(&DataKey::Finalized) {
+            return Err(Error::NotFinalized);
+        }
+
+        let mut tally: ProjectTally = env.storage().instance().get(&DataKey::Tally(pool.clone()))
+            .ok_or(Error::ProjectNotFound)?;
+
+        let pool_client = PoolClient::new(&env, &pool);
+        let entitled = match pool_client.get_disbursement_plan() {
+            Some(plan) if plan.released < plan.tranches.len() => {
+                let mut released_bps: i128 = 0;
+                for bps in plan.tranches.iter().take(plan.released as usize) {
+                    released_bps += bps as i128;
+                }
+                tally.matched.checked_mul(released_bps).ok_or(Error::Overflow)? / BPS_DENOMINATOR
+            }
+            _ => tally.matched,
+        };
+
+        let to_owner = entitled - tally.paid;
+        let to_admin = if pool_client.get_status() == PoolStatus::Abandoned {
+            tally.matched - entitled - tally.returned
+        } else {
+            0
+        };
+
+        if to_owner <= 0 && to_admin <= 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        let token: Address = env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        let token_client = token::Client::new(&env, &token);
+
+        if to_owner > 0 {
+            tally.paid = entitled;
+            token_client.transfer(&env.current_contract_address(), &pool_client.get_project_owner(), &to_owner);
+        }
+
+        if to_admin > 0 {
+            let admin: Address = env.storage().instance().get(&DataKey::Admin)
+                .ok_or(Error::NotInitialized)?;
+            tally.returned = tally.returned.checked_add(to_admin).ok_or(Error::Overflow)?;
+            token_client.transfer(&env.current_contract_address(), &admin, &to_admin);
+        }
+
+        env.storage().instance().set(&DataKey::Tally(pool.clone()), &tally);
+
+        // Emit match claim event
+        env.events().publish(
+            (symbol_short!("match_clm"),),
+            (pool, to_owner.max(0), to_admin.max(0))
+        );
+
+        Ok(to_owner.max(0))
+    }
+
+    /// Get the pools in the round
+    pub fn get_projects(env: Env) -> Result<Vec<Address>, Error> {
+        env.storage().instance().get(&DataKey::Projects)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get a project's tally
+    pub fn get_tally(env: Env, pool: Address) -> Option<ProjectTally> {
+        env.storage().instance().get(&DataKey::Tally(pool))
+    }
+
+    /// Get a project's quadratic score so far
+    pub fn get_score(env: Env, pool: Address) -> Result<i128, Error> {
+        let tally: ProjectTally = env.storage().instance().get(&DataKey::Tally(pool))
+            .ok_or(Error::ProjectNotFound)?;
+        score(&tally)
+    }
+
+    /// Get the matching fund
+    pub fn get_matching_fund(env: Env) -> Result<i128, Error> {
+        env.storage().instance().get(&DataKey::MatchingFund)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get the round end
+    pub fn get_round_end(env: Env) -> Result<u64, Error> {
+        env.storage().instance().get(&DataKey::RoundEnd)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Get the sybil-resistance hook, if configured
+    pub fn get_sybil_hook(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::SybilHook)
+    }
+
+    /// Check whether the round has been finalized
+    pub fn is_finalized(env: Env) -> bool {
+        env.storage().instance().has(&DataKey::Finalized)
+    }
+}
+
+// Quadratic score: the sum of square roots, squared
+fn score(tally: &ProjectTally) -> Result<i128, Error> {
+    tally.sqrt_sum.checked_mul(tally.sqrt_sum).ok_or(Error::Overflow)
+}
+
+// Integer square root, rounded down
+fn isqrt(value: i128) -> i128 {
+    if value < 2 {
+        return value;
+    }
+
+    // Newton's method from an initial guess at or above the root
+    let mut x = value / 2 + 1;
+    let mut y = (x + value / x) / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+
+    x
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,40 @@
+extern crate std;
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Env,
+};
+
+const ROUND_END: u64 = 1000;
+
+#[test]
+fn test_finalize_waits_for_round_end() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token_id = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let token = TokenClient::new(&env, &token_id);
+
+    let round_id = env.register_contract(None, MatchingRound);
+    let round = MatchingRoundClient::new(&env, &round_id);
+    round.initialize(&admin, &token_id, &ROUND_END, &None);
+
+    let funder = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_id).mint(&funder, &500);
+    round.fund(&funder, &300);
+
+    // An empty round cannot be finalized early and the fund stays open
+    assert_eq!(round.try_finalize(), Err(Ok(Error::RoundNotEnded)));
+    round.fund(&funder, &200);
+    assert_eq!(token.balance(&round_id), 500);
+
+    env.ledger().set_timestamp(ROUND_END);
+    round.finalize();
+
+    assert_eq!(token.balance(&admin), 500);
+    assert_eq!(token.balance(&round_id), 0);
+    assert_eq!(round.try_finalize(), Err(Ok(Error::AlreadyFinalized)));
+}
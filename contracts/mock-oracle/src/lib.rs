@@ -0,0 +1,91 @@
+#![no_std]
+
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, Address, Env, Symbol, symbol_short};
+
+// Contract errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    InvalidPrice = 3,
+}
+
+// Asset identifier, as in SEP-40
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+// Price record, as in SEP-40
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+// Storage keys
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Decimals,
+    Price(Asset),
+}
+
+/// Mock SEP-40 price oracle for local testing of multi-currency pools.
+///
+/// Prices are set directly by the admin, along with the timestamp they are
+/// reported at, so staleness handling can be exercised.
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    /// Initialize the oracle with its admin and price decimals
+    pub fn initialize(env: Env, admin: Address, decimals: u32) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Decimals, &decimals);
+
+        Ok(())
+    }
+
+    /// Report a price for an asset as of `timestamp` (admin only)
+    pub fn set_price(env: Env, asset: Asset, price: i128, timestamp: u64) -> Result<(), Error> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+
+        admin.require_auth();
+
+        if price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+
+        env.storage().instance().set(&DataKey::Price(asset.clone()), &PriceData { price, timestamp });
+
+        // Emit price event
+        env.events().publish(
+            (symbol_short!("price"),),
+            (asset, price, timestamp)
+        );
+
+        Ok(())
+    }
+
+    /// Query functions
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        env.storage().instance().get(&DataKey::Price(asset))
+    }
+
+    pub fn decimals(env: Env) -> Result<u32, Error> {
+        env.storage().instance().get(&DataKey::Decimals)
+            .ok_or(Error::NotInitialized)
+    }
+}
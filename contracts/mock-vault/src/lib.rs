@@ -0,0 +1,151 @@
+#![no_std]
+
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, token, Address, Env, symbol_short};
+
+// Contract errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    InvalidAmount = 3,
+    InsufficientShares = 4,
+    Overflow = 5,
+}
+
+// Storage keys
+#[contracttype]
+pub enum DataKey {
+    Token,
+    TotalShares,
+    Shares(Address),
+}
+
+/// Mock yield vault for local testing of escrow vault integration.
+///
+/// Shares are priced against the vault's token balance, so yield can be
+/// simulated by calling `accrue` to send extra tokens into the vault.
+#[contract]
+pub struct MockVault;
+
+#[contractimpl]
+impl MockVault {
+    /// Initialize the vault with its underlying token
+    pub fn initialize(env: Env, token: Address) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Token) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::TotalShares, &0i128);
+
+        Ok(())
+    }
+
+    /// Deposit tokens from `from` and mint shares
+    pub fn deposit(env: Env, from: Address, amount: i128) -> Result<i128, Error> {
+        from.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let token: Address = env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares)
+            .ok_or(Error::NotInitialized)?;
+
+        let token_client = token::Client::new(&env, &token);
+        let total_assets = token_client.balance(&env.current_contract_address());
+
+        let shares = if total_shares == 0 || total_assets == 0 {
+            amount
+        } else {
+            amount.checked_mul(total_shares).ok_or(Error::Overflow)? / total_assets
+        };
+
+        token_client.transfer(&from, &env.current_contract_address(), &amount);
+
+        let balance: i128 = env.storage().instance().get(&DataKey::Shares(from.clone())).unwrap_or(0);
+        let balance = balance.checked_add(shares).ok_or(Error::Overflow)?;
+        let total_shares = total_shares.checked_add(shares).ok_or(Error::Overflow)?;
+        env.storage().instance().set(&DataKey::Shares(from.clone()), &balance);
+        env.storage().instance().set(&DataKey::TotalShares, &total_shares);
+
+        // Emit deposit event
+        env.events().publish(
+            (symbol_short!("deposit"),),
+            (from, amount, shares)
+        );
+
+        Ok(shares)
+    }
+
+    /// Burn shares held by `owner` and pay out the underlying tokens
+    pub fn redeem(env: Env, owner: Address, shares: i128) -> Result<i128, Error> {
+        owner.require_auth();
+
+        if shares <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let token: Address = env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+        let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares)
+            .ok_or(Error::NotInitialized)?;
+        let balance: i128 = env.storage().instance().get(&DataKey::Shares(owner.clone())).unwrap_or(0);
+
+        if shares > balance {
+            return Err(Error::InsufficientShares);
+        }
+
+        let token_client = token::Client::new(&env, &token);
+        let total_assets = token_client.balance(&env.current_contract_address());
+        let amount = shares.checked_mul(total_assets).ok_or(Error::Overflow)? / total_shares;
+
+        // shares <= balance <= total_shares, so neither subtraction can underflow
+        env.storage().instance().set(&DataKey::Shares(owner.clone()), &(balance - shares));
+        env.storage().instance().set(&DataKey::TotalShares, &(total_shares - shares));
+
+        token_client.transfer(&env.current_contract_address(), &owner, &amount);
+
+        // Emit redemption event
+        env.events().publish(
+            (symbol_short!("redeem"),),
+            (owner, shares, amount)
+        );
+
+        Ok(amount)
+    }
+
+    /// Simulate yield by sending extra tokens into the vault
+    pub fn accrue(env: Env, from: Address, amount: i128) -> Result<(), Error> {
+        from.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let token: Address = env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)?;
+
+        token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &amount);
+
+        Ok(())
+    }
+
+    /// Query functions
+    pub fn token(env: Env) -> Result<Address, Error> {
+        env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn shares_of(env: Env, owner: Address) -> i128 {
+        env.storage().instance().get(&DataKey::Shares(owner)).unwrap_or(0)
+    }
+
+    pub fn total_shares(env: Env) -> i128 {
+        env.storage().instance().get(&DataKey::TotalShares).unwrap_or(0)
+    }
+}
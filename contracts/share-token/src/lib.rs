@@ -0,0 +1,313 @@
+#![no_std]
+
+use soroban_sdk::{contract, contractimpl, contracterror, contracttype, Address, Env, String, symbol_short};
+
+// Contract errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    InvalidAmount = 3,
+    InsufficientBalance = 4,
+    InsufficientAllowance = 5,
+    InvalidExpiration = 6,
+    Overflow = 7,
+}
+
+// Allowance granted by `from` to `spender`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+// Storage keys
+#[contracttype]
+pub enum DataKey {
+    Admin, // Minter, normally the crowdfunding pool
+    Decimals,
+    Name,
+    Symbol,
+    TotalSupply,
+    Balance(Address),            // Persistent
+    Allowance(Address, Address), // (from, spender), temporary
+}
+
+const BALANCE_TTL_THRESHOLD: u32 = 518_400;
+const BALANCE_TTL_EXTEND_TO: u32 = 3_110_400;
+
+/// SEP-41 share token minted to crowdfunding backers.
+///
+/// The admin (the pool contract) is the only minter; everything else follows
+/// the standard token interface so shares can be transferred and used by
+/// revenue-sharing and governance features.
+#[contract]
+pub struct ShareToken;
+
+#[contractimpl]
+impl ShareToken {
+    /// Initialize the share token (admin must authorize)
+    pub fn initialize(env: Env, admin: Address, decimals: u32, name: String, symbol: String) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Decimals, &decimals);
+        env.storage().instance().set(&DataKey::Name, &name);
+        env.storage().instance().set(&DataKey::Symbol, &symbol);
+        env.storage().instance().set(&DataKey::TotalSupply, &0i128);
+
+        Ok(())
+    }
+
+    /// Mint shares (admin only)
+    pub fn mint(env: Env, to: Address, amount: i128) -> Result<(), Error> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+
+        admin.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let supply: i128 = env.storage().instance().get(&DataKey::TotalSupply)
+            .ok_or(Error::NotInitialized)?;
+        env.storage().instance().set(&DataKey::TotalSupply, &supply.checked_add(amount).ok_or(Error::Overflow)?);
+        credit(&env, &to, amount)?;
+
+        // Emit mint event
+        env.events().publish(
+            (symbol_short!("mint"), admin, to),
+            amount
+        );
+
+        Ok(())
+    }
+
+    /// Hand minting rights to a new admin
+    pub fn set_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)?;
+
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+
+        // Emit admin change event
+        env.events().publish(
+            (symbol_short!("set_admin"), admin),
+            new_admin
+        );
+
+        Ok(())
+    }
+
+    /// SEP-41 token interface
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        allowance_of(&env, &from, &spender).amount
+    }
+
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) -> Result<(), Error> {
+        from.require_auth();
+
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        if amount > 0 && expiration_ledger < env.ledger().sequence() {
+            return Err(Error::InvalidExpiration);
+        }
+
+        let key = DataKey::Allowance(from.clone(), spender.clone());
+        env.storage().temporary().set(&key, &AllowanceValue { amount, expiration_ledger });
+        if amount > 0 {
+            let live_for = expiration_ledger - env.ledger().sequence();
+            env.storage().temporary().extend_ttl(&key, live_for, live_for);
+        }
+
+        // Emit approval event
+        env.events().publish(
+            (symbol_short!("approve"), from, spender),
+            (amount, expiration_ledger)
+        );
+
+        Ok(())
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        balance_of(&env, &id)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), Error> {
+        from.require_auth();
+
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        debit(&env, &from, amount)?;
+        credit(&env, &to, amount)?;
+
+        // Emit transfer event
+        env.events().publish(
+            (symbol_short!("transfer"), from, to),
+            amount
+        );
+
+        Ok(())
+    }
+
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) -> Result<(), Error> {
+        spender.require_auth();
+
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        spend_allowance(&env, &from, &spender, amount)?;
+        debit(&env, &from, amount)?;
+        credit(&env, &to, amount)?;
+
+        // Emit transfer event
+        env.events().publish(
+            (symbol_short!("transfer"), from, to),
+            amount
+        );
+
+        Ok(())
+    }
+
+    pub fn burn(env: Env, from: Address, amount: i128) -> Result<(), Error> {
+        from.require_auth();
+
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        debit(&env, &from, amount)?;
+        reduce_supply(&env, amount)?;
+
+        // Emit burn event
+        env.events().publish(
+            (symbol_short!("burn"), from),
+            amount
+        );
+
+        Ok(())
+    }
+
+    pub fn burn_from(env: Env, spender: Address, from: Address, amount: i128) -> Result<(), Error> {
+        spender.require_auth();
+
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        spend_allowance(&env, &from, &spender, amount)?;
+        debit(&env, &from, amount)?;
+        reduce_supply(&env, amount)?;
+
+        // Emit burn event
+        env.events().publish(
+            (symbol_short!("burn"), from),
+            amount
+        );
+
+        Ok(())
+    }
+
+    pub fn decimals(env: Env) -> Result<u32, Error> {
+        env.storage().instance().get(&DataKey::Decimals)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn name(env: Env) -> Result<String, Error> {
+        env.storage().instance().get(&DataKey::Name)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn symbol(env: Env) -> Result<String, Error> {
+        env.storage().instance().get(&DataKey::Symbol)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Query functions
+    pub fn total_supply(env: Env) -> Result<i128, Error> {
+        env.storage().instance().get(&DataKey::TotalSupply)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn admin(env: Env) -> Result<Address, Error> {
+        env.storage().instance().get(&DataKey::Admin)
+            .ok_or(Error::NotInitialized)
+    }
+}
+
+fn balance_of(env: &Env, id: &Address) -> i128 {
+    env.storage().persistent().get(&DataKey::Balance(id.clone())).unwrap_or(0)
+}
+
+fn set_balance(env: &Env, id: &Address, amount: i128) {
+    let key = DataKey::Balance(id.clone());
+    env.storage().persistent().set(&key, &amount);
+    env.storage().persistent().extend_ttl(&key, BALANCE_TTL_THRESHOLD, BALANCE_TTL_EXTEND_TO);
+}
+
+fn credit(env: &Env, id: &Address, amount: i128) -> Result<(), Error> {
+    let balance = balance_of(env, id).checked_add(amount).ok_or(Error::Overflow)?;
+    set_balance(env, id, balance);
+    Ok(())
+}
+
+fn debit(env: &Env, id: &Address, amount: i128) -> Result<(), Error> {
+    let balance = balance_of(env, id);
+    if balance < amount {
+        return Err(Error::InsufficientBalance);
+    }
+    set_balance(env, id, balance - amount);
+    Ok(())
+}
+
+fn reduce_supply(env: &Env, amount: i128) -> Result<(), Error> {
+    let supply: i128 = env.storage().instance().get(&DataKey::TotalSupply)
+        .ok_or(Error::NotInitialized)?;
+    env.storage().instance().set(&DataKey::TotalSupply, &supply.checked_sub(amount).ok_or(Error::Overflow)?);
+    Ok(())
+}
+
+// Expired allowances read as zero
+fn allowance_of(env: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+    let allowance: Option<AllowanceValue> = env.storage().temporary()
+        .get(&DataKey::Allowance(from.clone(), spender.clone()));
+
+    match allowance {
+        Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance,
+        _ => AllowanceValue { amount: 0, expiration_ledger: 0 },
+    }
+}
+
+fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) -> Result<(), Error> {
+    let allowance = allowance_of(env, from, spender);
+    if allowance.amount < amount {
+        return Err(Error::InsufficientAllowance);
+    }
+
+    if amount > 0 {
+        env.storage().temporary().set(
+            &DataKey::Allowance(from.clone(), spender.clone()),
+            &AllowanceValue {
+                amount: allowance.amount - amount,
+                expiration_ledger: allowance.expiration_ledger,
+            },
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,503 @@
+#![no_std]
+
+use soroban_sdk::{
+    contract, contractimpl, contracterror, contracttype, token, Address, BytesN, Env, Vec, symbol_short,
+};
+
+// Contract errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    InvalidAmount = 3,
+    InvalidPeriod = 4,
+    TierNotFound = 5,
+    TooManyTiers = 6,
+    SubscriptionNotFound = 7,
+    AlreadySubscribed = 8,
+    NotActive = 9,
+    NotPaused = 10,
+    NotDue = 11,
+    PaymentFailed = 12,
+    Overflow = 13,
+}
+
+// Subscription tier offered by the creator
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionTier {
+    pub id: u32,
+    pub amount: i128, // Pulled once per period
+    pub metadata_hash: BytesN<32>,
+}
+
+// Subscription lifecycle
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SubscriptionStatus {
+    Active,
+    Paused,
+    Cancelled,
+    Lapsed, // Too many consecutive missed payments
+}
+
+// A supporter's subscription
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subscription {
+    pub subscriber: Address,
+    pub tier: u32,
+    pub amount: i128,
+    pub status: SubscriptionStatus,
+    pub started_at: u64,
+    pub next_payment: u64,
+    pub missed_payments: u32, // Consecutive, reset by a successful payment
+    pub total_missed: u32,
+    pub total_paid: i128,
+}
+
+// Storage keys
+#[contracttype]
+pub enum DataKey {
+    Creator,
+    Token,
+    Period,
+    MaxMissed,
+    Tiers,
+    SubscriberCount,
+    SubscriberAt(u32),        // Subscriber index, persistent
+    Subscription(Address),    // Persistent
+}
+
+const SUBSCRIBER_TTL_THRESHOLD: u32 = 518_400;
+const SUBSCRIBER_TTL_EXTEND_TO: u32 = 3_110_400;
+
+// Maximum number of tiers a creator can offer
+const MAX_TIERS: u32 = 10;
+
+/// Recurring subscription pledges for a creator.
+///
+/// Supporters approve a token allowance for this contract and subscribe to a
+/// tier; the first period is paid on subscribing and each later period is
+/// pulled from the allowance once due. Payments that cannot be pulled are
+/// recorded as missed, and a subscription lapses after too many in a row.
+#[contract]
+pub struct SubscriptionContract;
+
+#[contractimpl]
+impl SubscriptionContract {
+    /// Initialize the creator's subscriptions. `max_missed` is the number of
+    /// consecutive missed payments after which a subscription lapses (0 to never lapse).
+    pub fn initialize(env: Env, creator: Address, token: Address, period: u64, max_missed: u32) -> Result<(), Error> {
+        if env.storage().instance().has(&DataKey::Creator) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        if period == 0 {
+            return Err(Error::InvalidPeriod);
+        }
+
+        creator.require_auth();
+
+        env.storage().instance().set(&DataKey::Creator, &creator);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::Period, &period);
+        env.storage().instance().set(&DataKey::MaxMissed, &max_missed);
+        env.storage().instance().set(&DataKey::Tiers, &Vec::<SubscriptionTier>::new(&env));
+        env.storage().instance().set(&DataKey::SubscriberCount, &0u32);
+
+        // Emit initialization event
+        env.events().publish(
+            (symbol_short!("init"),),
+            (creator, token, period, max_missed)
+        );
+
+        Ok(())
+    }
+
+    /// Add a subscription tier (creator only)
+    pub fn add_tier(env: Env, amount: i128, metadata_hash: BytesN<32>) -> Result<u32, Error> {
+        let creator: Address = env.storage().instance().get(&DataKey::Creator)
+            .ok_or(Error::NotInitialized)?;
+
+        creator.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut tiers: Vec<SubscriptionTier> = env.storage().instance().get(&DataKey::Tiers)
+            .ok_or(Error::NotInitialized)?;
+
+        if tiers.len() >= MAX_TIERS {
+            return Err(Error::TooManyTiers);
+        }
+
+        let id = tiers.len();
+        tiers.push_back(SubscriptionTier {
+            id,
+            amount,
+            metadata_hash,
+        });
+        env.storage().instance().set(&DataKey::Tiers, &tiers);
+
+        // Emit tier event
+        env.events().publish(
+            (symbol_short!("tier"),),
+            (id, amount)
+        );
+
+        Ok(id)
+    }
+
+    /// Subscribe to a tier, paying the first period from the subscriber's
+    /// allowance. A cancelled or lapsed subscription can be restarted; a
+    /// cancelled one that was paid up keeps its current period instead of being
+    /// charged again, while a lapsed one is charged straight away.
+    pub fn subscribe(env: Env, subscriber: Address, tier: u32) -> Result<(), Error> {
+        subscriber.require_auth();
+
+        let amount = tier_amount(&env, tier)?;
+        let period: u64 = env.storage().instance().get(&DataKey::Period)
+            .ok_or(Error::NotInitialized)?;
+
+        let existing: Option<Subscription> = env.storage().persistent().get(&DataKey::Subscription(subscriber.clone()));
+        match &existing {
+            Some(subscription) if matches!(subscription.status, SubscriptionStatus::Active | SubscriptionStatus::Paused) => {
+                return Err(Error::AlreadySubscribed);
+            }
+            Some(_) => {}
+            None => {
+                // Index new subscribers so keepers can page through them
+                let count: u32 = env.storage().instance().get(&DataKey::SubscriberCount)
+                    .ok_or(Error::NotInitialized)?;
+                let index_key = DataKey::SubscriberAt(count);
+                env.storage().persistent().set(&index_key, &subscriber);
+                env.storage().persistent().extend_ttl(&index_key, SUBSCRIBER_TTL_THRESHOLD, SUBSCRIBER_TTL_EXTEND_TO);
+                env.storage().instance().set(&DataKey::SubscriberCount, &count.checked_add(1).ok_or(Error::Overflow)?);
+            }
+        }
+
+        // Payment history carries over when a subscription is restarted. The
+        // current period only counts as paid if the subscription was cancelled
+        // with no missed payments outstanding, since a missed pull still moves
+        // `next_payment` forward.
+        let (mut total_paid, total_missed, paid_until) = match existing {
+            Some(subscription) => {
+                let paid_up = subscription.status == SubscriptionStatus::Cancelled
+                    && subscription.missed_payments == 0;
+                let paid_until = if paid_up { subscription.next_payment } else { 0 };
+                (subscription.total_paid, subscription.total_missed, paid_until)
+            }
+            None => (0, 0, 0),
+        };
+
+        let now = env.ledger().timestamp();
+        let next_payment = if paid_until > now {
+            paid_until
+        } else {
+            if !pull_payment(&env, &subscriber, amount)? {
+                return Err(Error::PaymentFailed);
+            }
+            total_paid = total_paid.checked_add(amount).ok_or(Error::Overflow)?;
+            now.checked_add(period).ok_or(Error::Overflow)?
+        };
+
+        set_subscription(&env, &Subscription {
+            subscriber: subscriber.clone(),
+            tier,
+            amount,
+            status: SubscriptionStatus::Active,
+            started_at: now,
+            next_payment,
+            missed_payments: 0,
+            total_missed,
+            total_paid,
+        });
+
+        // Emit subscription event
+        env.events().publish(
+            (symbol_short!("subscribe"),),
+            (subscriber, tier, amount)
+        );
+
+        Ok(())
+    }
+
+    /// Pull the current period's payment from a subscriber once it is due
+    /// (anyone, typically the creator or a keeper). Returns whether the payment
+    /// went through; a failed pull is recorded as missed and the period skipped.
+    pub fn collect(env: Env, subscriber: Address) -> Result<bool, Error> {
+        collect_payment(&env, &subscriber)
+    }
+
+    /// Pull due payments for a page of subscribers (anyone, typically a keeper).
+    /// Subscriptions that are not active or not yet due are skipped.
+    /// Returns the number of payments collected.
+    pub fn collect_due(env: Env, start: u32, limit: u32) -> Result<u32, Error> {
+        let count: u32 = env.storage().instance().get(&DataKey::SubscriberCount)
+            .ok_or(Error::NotInitialized)?;
+        let end = start.saturating_add(limit).min(count);
+        let now = env.ledger().timestamp();
+
+        let mut collected: u32 = 0;
+        for index in start..end {
+            let subscriber: Address = env.storage().persistent().get(&DataKey::SubscriberAt(index))
+                .ok_or(Error::NotInitialized)?;
+            let subscription = subscription_of(&env, &subscriber)?;
+            if subscription.status != SubscriptionStatus::Active || now < subscription.next_payment {
+                continue;
+            }
+
+            if collect_payment(&env, &subscriber)? {
+                collected += 1;
+            }
+        }
+
+        // Emit batch collection event
+        env.events().publish(
+            (symbol_short!("collected"),),
+            (start, end, collected)
+        );
+
+        Ok(collected)
+    }
+
+    /// Switch to another tier, charged from the next payment onwards
+    pub fn change_tier(env: Env, subscriber: Address, tier: u32) -> Result<(), Error> {
+        subscriber.require_auth();
+
+        let mut subscription = subscription_of(&env, &subscriber)?;
+        if !matches!(subscription.status, SubscriptionStatus::Active | SubscriptionStatus::Paused) {
+            return Err(Error::NotActive);
+        }
+
+        subscription.tier = tier;
+        subscription.amount = tier_amount(&env, tier)?;
+        set_subscription(&env, &subscription);
+
+        // Emit tier change event
+        env.events().publish(
+            (symbol_short!("tier_chg"),),
+            (subscriber, tier, subscription.amount)
+        );
+
+        Ok(())
+    }
+
+    /// Pause an active subscription; no payments are pulled while paused
+    pub fn pause(env: Env, subscriber: Address) -> Result<(), Error> {
+        subscriber.require_auth();
+
+        let mut subscription = subscription_of(&env, &subscriber)?;
+        if subscription.status != SubscriptionStatus::Active {
+            return Err(Error::NotActive);
+        }
+
+        subscription.status = SubscriptionStatus::Paused;
+        set_subscription(&env, &subscription);
+
+        // Emit pause event
+        env.events().publish(
+            (symbol_short!("pause"),),
+            subscriber
+        );
+
+        Ok(())
+    }
+
+    /// Resume a paused subscription. Time spent paused is not charged: the next
+    /// payment falls due at the end of the already paid period, or immediately
+    /// if that has passed.
+    pub fn resume(env: Env, subscriber: Address) -> Result<(), Error> {
+        subscriber.require_auth();
+
+        let mut subscription = subscription_of(&env, &subscriber)?;
+        if subscription.status != SubscriptionStatus::Paused {
+            return Err(Error::NotPaused);
+        }
+
+        subscription.status = SubscriptionStatus::Active;
+        subscription.next_payment = subscription.next_payment.max(env.ledger().timestamp());
+        set_subscription(&env, &subscription);
+
+        // Emit resume event
+        env.events().publish(
+            (symbol_short!("resume"),),
+            (subscriber, subscription.next_payment)
+        );
+
+        Ok(())
+    }
+
+    /// Cancel an active or paused subscription
+    pub fn cancel(env: Env, subscriber: Address) -> Result<(), Error> {
+        subscriber.require_auth();
+
+        let mut subscription = subscription_of(&env, &subscriber)?;
+        if !matches!(subscription.status, SubscriptionStatus::Active | SubscriptionStatus::Paused) {
+            return Err(Error::NotActive);
+        }
+
+        subscription.status = SubscriptionStatus::Cancelled;
+        set_subscription(&env, &subscription);
+
+        // Emit cancellation event
+        env.events().publish(
+            (symbol_short!("cancel"),),
+            subscriber
+        );
+
+        Ok(())
+    }
+
+    /// Query functions
+    pub fn get_subscription(env: Env, subscriber: Address) -> Result<Subscription, Error> {
+        subscription_of(&env, &subscriber)
+    }
+
+    pub fn get_tiers(env: Env) -> Result<Vec<SubscriptionTier>, Error> {
+        env.storage().instance().get(&DataKey::Tiers)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn get_creator(env: Env) -> Result<Address, Error> {
+        env.storage().instance().get(&DataKey::Creator)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn get_token(env: Env) -> Result<Address, Error> {
+        env.storage().instance().get(&DataKey::Token)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn get_period(env: Env) -> Result<u64, Error> {
+        env.storage().instance().get(&DataKey::Period)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn get_max_missed(env: Env) -> Result<u32, Error> {
+        env.storage().instance().get(&DataKey::MaxMissed)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn get_subscriber_count(env: Env) -> Result<u32, Error> {
+        env.storage().instance().get(&DataKey::SubscriberCount)
+            .ok_or(Error::NotInitialized)
+    }
+
+    /// Page through subscribers in order of first subscription
+    pub fn get_subscribers(env: Env, start: u32, limit: u32) -> Result<Vec<Address>, Error> {
+        let count: u32 = env.storage().instance().get(&DataKey::SubscriberCount)
+            .ok_or(Error::NotInitialized)?;
+
+        let mut subscribers: Vec<Address> = Vec::new(&env);
+        let end = start.saturating_add(limit).min(count);
+        for index in start..end {
+            let subscriber: Address = env.storage().persistent().get(&DataKey::SubscriberAt(index))
+                .ok_or(Error::NotInitialized)?;
+            subscribers.push_back(subscriber);
+        }
+
+        Ok(subscribers)
+    }
+}
+
+// Collect a due payment, recording a miss (and lapsing the subscription once
+// too many are missed in a row) when the pull fails
+fn collect_payment(env: &Env, subscriber: &Address) -> Result<bool, Error> {
+    let mut subscription = subscription_of(env, subscriber)?;
+    if subscription.status != SubscriptionStatus::Active {
+        return Err(Error::NotActive);
+    }
+
+    if env.ledger().timestamp() < subscription.next_payment {
+        return Err(Error::NotDue);
+    }
+
+    let period: u64 = env.storage().instance().get(&DataKey::Period)
+        .ok_or(Error::NotInitialized)?;
+    let due = subscription.next_payment;
+    subscription.next_payment = due.checked_add(period).ok_or(Error::Overflow)?;
+
+    let paid = pull_payment(env, subscriber, subscription.amount)?;
+    if paid {
+        subscription.total_paid = subscription.total_paid.checked_add(subscription.amount).ok_or(Error::Overflow)?;
+        subscription.missed_payments = 0;
+
+        // Emit payment event
+        env.events().publish(
+            (symbol_short!("payment"),),
+            (subscriber.clone(), subscription.amount, due)
+        );
+    } else {
+        subscription.missed_payments = subscription.missed_payments.checked_add(1).ok_or(Error::Overflow)?;
+        subscription.total_missed = subscription.total_missed.checked_add(1).ok_or(Error::Overflow)?;
+
+        // Emit missed payment event
+        env.events().publish(
+            (symbol_short!("missed"),),
+            (subscriber.clone(), subscription.amount, due, subscription.missed_payments)
+        );
+
+        let max_missed: u32 = env.storage().instance().get(&DataKey::MaxMissed)
+            .ok_or(Error::NotInitialized)?;
+        if max_missed > 0 && subscription.missed_payments >= max_missed {
+            subscription.status = SubscriptionStatus::Lapsed;
+
+            // Emit lapse event
+            env.events().publish(
+                (symbol_short!("lapsed"),),
+                subscriber.clone()
+            );
+        }
+    }
+
+    set_subscription(env, &subscription);
+
+    Ok(paid)
+}
+
+// Pull `amount` from the subscriber's allowance to the creator; false if the
+// allowance or balance is insufficient
+fn pull_payment(env: &Env, subscriber: &Address, amount: i128) -> Result<bool, Error> {
+    let creator: Address = env.storage().instance().get(&DataKey::Creator)
+        .ok_or(Error::NotInitialized)?;
+    let token_address: Address = env.storage().instance().get(&DataKey::Token)
+        .ok_or(Error::NotInitialized)?;
+
+    let token_client = token::Client::new(env, &token_address);
+    let result = token_client.try_transfer_from(
+        &env.current_contract_address(),
+        subscriber,
+        &creator,
+        &amount,
+    );
+
+    Ok(matches!(result, Ok(Ok(()))))
+}
+
+// Amount charged per period for a tier
+fn tier_amount(env: &Env, tier: u32) -> Result<i128, Error> {
+    let tiers: Vec<SubscriptionTier> = env.storage().instance().get(&DataKey::Tiers)
+        .ok_or(Error::NotInitialized)?;
+    tiers.get(tier).map(|tier| tier.amount).ok_or(Error::TierNotFound)
+}
+
+// Load a subscriber's subscription
+fn subscription_of(env: &Env, subscriber: &Address) -> Result<Subscription, Error> {
+    env.storage().persistent().get(&DataKey::Subscription(subscriber.clone()))
+        .ok_or(Error::SubscriptionNotFound)
+}
+
+// Store a subscription and extend its TTL
+fn set_subscription(env: &Env, subscription: &Subscription) {
+    let key = DataKey::Subscription(subscription.subscriber.clone());
+    env.storage().persistent().set(&key, subscription);
+    env.storage().persistent().extend_ttl(&key, SUBSCRIBER_TTL_THRESHOLD, SUBSCRIBER_TTL_EXTEND_TO);
+}
+
+#[cfg(test)]
+mod test;
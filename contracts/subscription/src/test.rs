@@ -0,0 +1,85 @@
+extern crate std;
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Env,
+};
+
+const PERIOD: u64 = 100;
+
+struct Setup<'a> {
+    env: Env,
+    contract: SubscriptionContractClient<'a>,
+    token: TokenClient<'a>,
+    creator: Address,
+    subscriber: Address,
+}
+
+fn setup<'a>(balance: i128) -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token_id = env.register_stellar_asset_contract_v2(admin).address();
+    let token = TokenClient::new(&env, &token_id);
+
+    let creator = Address::generate(&env);
+    let contract_id = env.register_contract(None, SubscriptionContract);
+    let contract = SubscriptionContractClient::new(&env, &contract_id);
+    contract.initialize(&creator, &token_id, &PERIOD, &2);
+    contract.add_tier(&10, &BytesN::from_array(&env, &[0; 32]));
+
+    let subscriber = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_id).mint(&subscriber, &balance);
+    token.approve(&subscriber, &contract_id, &1000, &10_000);
+
+    Setup { env, contract, token, creator, subscriber }
+}
+
+#[test]
+fn test_cancelled_restart_keeps_paid_period() {
+    let s = setup(100);
+
+    s.contract.subscribe(&s.subscriber, &0);
+    s.contract.cancel(&s.subscriber);
+
+    s.env.ledger().set_timestamp(50);
+    s.contract.subscribe(&s.subscriber, &0);
+
+    let subscription = s.contract.get_subscription(&s.subscriber);
+    assert_eq!(subscription.next_payment, PERIOD);
+    assert_eq!(subscription.total_paid, 10);
+    assert_eq!(s.token.balance(&s.creator), 10);
+}
+
+#[test]
+fn test_lapsed_restart_is_charged() {
+    let s = setup(10);
+
+    s.contract.subscribe(&s.subscriber, &0);
+
+    // Two missed pulls lapse the subscription; each still moves the due date on
+    s.env.ledger().set_timestamp(PERIOD);
+    assert!(!s.contract.collect(&s.subscriber));
+    s.env.ledger().set_timestamp(2 * PERIOD);
+    assert!(!s.contract.collect(&s.subscriber));
+
+    let subscription = s.contract.get_subscription(&s.subscriber);
+    assert_eq!(subscription.status, SubscriptionStatus::Lapsed);
+    assert_eq!(subscription.next_payment, 3 * PERIOD);
+
+    // Restarting inside the unpaid period must pull a payment
+    assert_eq!(s.contract.try_subscribe(&s.subscriber, &0), Err(Ok(Error::PaymentFailed)));
+
+    StellarAssetClient::new(&s.env, &s.token.address).mint(&s.subscriber, &10);
+    s.contract.subscribe(&s.subscriber, &0);
+
+    let subscription = s.contract.get_subscription(&s.subscriber);
+    assert_eq!(subscription.status, SubscriptionStatus::Active);
+    assert_eq!(subscription.next_payment, 3 * PERIOD);
+    assert_eq!(subscription.total_paid, 20);
+    assert_eq!(subscription.total_missed, 2);
+    assert_eq!(s.token.balance(&s.creator), 20);
+}